@@ -0,0 +1,97 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    ops::Deref,
+};
+
+use uuid::Uuid;
+
+use crate::UuidMap;
+
+/// A [`UuidMap`] whose values are unique, and which can thus also be used to look up
+/// the UUID of a value.
+///
+/// Each UUID has at most one value, and each value at most one UUID. Inserting an
+/// entry evicts the other entries it conflicts with, on either side.
+///
+/// See [`UuidBiMap::insert()`].
+pub struct UuidBiMap<V> {
+    map: UuidMap<V>,
+    uuids: HashMap<V, Uuid>,
+}
+
+impl<V: Hash + Eq + Clone> UuidBiMap<V> {
+    /// Creates an empty [`UuidBiMap`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            map: UuidMap::new(),
+            uuids: HashMap::new(),
+        }
+    }
+
+    /// Returns the value of the given UUID.
+    #[inline]
+    pub fn get_by_uuid(&self, uuid: &Uuid) -> Option<&V> {
+        self.map.get(uuid)
+    }
+
+    /// Returns the UUID of the given value.
+    #[inline]
+    pub fn get_by_value(&self, value: &V) -> Option<&Uuid> {
+        self.uuids.get(value)
+    }
+
+    /// Inserts an entry, evicting the entries of the UUID and of the value if there
+    /// were any.
+    ///
+    /// Returns the previous value of the UUID, and the previous UUID of the value if
+    /// it was another one. For example, inserting `(a, x)` when the map contains
+    /// `(a, y)` and `(b, x)` removes both entries and returns `(Some(y), Some(b))`.
+    pub fn insert(&mut self, uuid: Uuid, value: V) -> (Option<V>, Option<Uuid>) {
+        let previous_value = self.remove_by_uuid(&uuid);
+        let previous_uuid = self.remove_by_value(&value);
+
+        self.map.insert(uuid, value.clone());
+        self.uuids.insert(value, uuid);
+
+        (previous_value, previous_uuid)
+    }
+
+    /// Removes the entry of the given UUID, and returns its value if there was one.
+    pub fn remove_by_uuid(&mut self, uuid: &Uuid) -> Option<V> {
+        let value = self.map.remove(uuid)?;
+        self.uuids.remove(&value);
+        Some(value)
+    }
+
+    /// Removes the entry of the given value, and returns its UUID if there was one.
+    pub fn remove_by_value(&mut self, value: &V) -> Option<Uuid> {
+        let uuid = self.uuids.remove(value)?;
+        self.map.remove(&uuid);
+        Some(uuid)
+    }
+}
+
+impl<V: Hash + Eq + Clone> Default for UuidBiMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Deref for UuidBiMap<V> {
+    type Target = UuidMap<V>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<V: Debug> Debug for UuidBiMap<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}
@@ -0,0 +1,20 @@
+use std::ops::Deref;
+
+/// The 16 bytes of a UUID, aligned on 16 bytes so that they can be loaded into SIMD
+/// registers directly.
+///
+/// See [`UuidSet::keys_blocks()`][1].
+///
+/// [1]: crate::UuidSet::keys_blocks
+#[repr(C, align(16))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct UuidBlock(pub [u8; 16]);
+
+impl Deref for UuidBlock {
+    type Target = [u8; 16];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,25 @@
+use crate::UuidMap;
+
+/// The differences between two [`UuidMap`]s.
+///
+/// See [`UuidMap::diff()`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MapDiff<V> {
+    /// The entries which are only in the new map.
+    pub added: UuidMap<V>,
+
+    /// The entries which are only in the old map.
+    pub removed: UuidMap<V>,
+
+    /// The entries which are in both maps, but with different values, as their old
+    /// and new values.
+    pub changed: UuidMap<(V, V)>,
+}
+
+impl<V> MapDiff<V> {
+    /// Returns whether both maps have the same entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
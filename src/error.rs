@@ -0,0 +1,95 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use uuid::Uuid;
+
+/// An error returned by [`UuidSet::from_canonical_bytes()`][1].
+///
+/// [1]: crate::UuidSet::from_canonical_bytes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CanonicalBytesError {
+    /// The length of the input isn't a multiple of 16.
+    InvalidLength(usize),
+
+    /// The UUID isn't strictly greater than the one before it.
+    Unsorted(Uuid),
+
+    /// The UUID can't be hashed by [`UuidHasher`][1].
+    ///
+    /// [1]: crate::UuidHasher
+    Unsupported(Uuid),
+}
+
+/// An error returned by [`UuidIndexSet::from_prefix_compressed()`][1].
+///
+/// [1]: crate::UuidIndexSet::from_prefix_compressed
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PrefixCompressedError {
+    /// The input ends in the middle of a UUID.
+    Truncated,
+
+    /// The length of the prefix shared with the previous UUID is greater than 15, or
+    /// isn't 0 for the first UUID.
+    InvalidPrefixLength(u8),
+
+    /// The UUID isn't strictly greater than the one before it.
+    Unsorted(Uuid),
+
+    /// The UUID can't be hashed by [`UuidHasher`][1].
+    ///
+    /// [1]: crate::UuidHasher
+    Unsupported(Uuid),
+}
+
+/// An error returned when a UUID can't be hashed by [`UuidHasher`][1], e.g. by
+/// [`UuidMap::try_insert()`][2] or [`UuidMap::try_extend()`][3].
+///
+/// The version of the UUID can be retrieved with [`Uuid::get_version_num()`].
+///
+/// [1]: crate::UuidHasher
+/// [2]: crate::UuidMap::try_insert
+/// [3]: crate::UuidMap::try_extend
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UnsupportedVersionError(pub Uuid);
+
+impl Display for CanonicalBytesError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => {
+                write!(f, "expected a multiple of 16 bytes, got {len}")
+            }
+            Self::Unsorted(uuid) => write!(f, "UUID `{uuid}` is out of order or duplicated"),
+            Self::Unsupported(uuid) => write!(f, "UUID `{uuid}` is not supported"),
+        }
+    }
+}
+
+impl Error for CanonicalBytesError {}
+
+impl Display for PrefixCompressedError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "unexpected end of input"),
+            Self::InvalidPrefixLength(len) => write!(f, "invalid shared prefix length {len}"),
+            Self::Unsorted(uuid) => write!(f, "UUID `{uuid}` is out of order or duplicated"),
+            Self::Unsupported(uuid) => write!(f, "UUID `{uuid}` is not supported"),
+        }
+    }
+}
+
+impl Error for PrefixCompressedError {}
+
+impl Display for UnsupportedVersionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UUID `{}` of version {} is not supported",
+            self.0,
+            self.0.get_version_num()
+        )
+    }
+}
+
+impl Error for UnsupportedVersionError {}
@@ -0,0 +1,83 @@
+use std::hash::BuildHasher;
+
+use uuid::Uuid;
+
+use crate::{UuidBuildHasher, hasher};
+
+/// The number of bits of each hash used to pick a register.
+const PRECISION: u32 = 14;
+
+/// The number of registers.
+const REGISTERS: usize = 1 << PRECISION;
+
+/// An estimator of the number of distinct UUIDs in a stream, using HyperLogLog.
+///
+/// This uses a fixed 16KiB of memory regardless of the number of UUIDs, and has a
+/// standard error of about 0.8%. It can be used to size a [`UuidMap`][1] or a
+/// [`UuidSet`][2] before building it.
+///
+/// [1]: crate::UuidMap
+/// [2]: crate::UuidSet
+#[derive(Clone)]
+pub struct UuidCardinalityEstimator {
+    registers: Box<[u8]>,
+}
+
+impl UuidCardinalityEstimator {
+    /// Creates an empty [`UuidCardinalityEstimator`].
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS].into_boxed_slice(),
+        }
+    }
+
+    /// Adds a UUID to the estimator.
+    pub fn add(&mut self, uuid: &Uuid) {
+        // The hashes of UUIDv7s generated in the same millisecond can share most of
+        // their bits, so they are avalanched first.
+        let hash = hasher::avalanche(UuidBuildHasher.hash_one(uuid));
+
+        let idx = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() + 1;
+
+        self.registers[idx] = self.registers[idx].max(rank as u8);
+    }
+
+    /// Returns the estimated number of distinct UUIDs added to the estimator.
+    pub fn estimate(&self) -> u64 {
+        let registers = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / registers);
+
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|rank| 2f64.powi(-i32::from(*rank)))
+            .sum();
+
+        let estimate = alpha * registers * registers / sum;
+        let zeros = self.registers.iter().filter(|rank| **rank == 0).count();
+
+        // HyperLogLog is biased for small cardinalities, for which linear counting is
+        // used instead.
+        if estimate <= 2.5 * registers && zeros > 0 {
+            return (registers * (registers / zeros as f64).ln()).round() as u64;
+        }
+
+        estimate.round() as u64
+    }
+}
+
+impl Default for UuidCardinalityEstimator {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Extend<Uuid> for UuidCardinalityEstimator {
+    fn extend<T: IntoIterator<Item = Uuid>>(&mut self, iter: T) {
+        for uuid in iter {
+            self.add(&uuid);
+        }
+    }
+}
@@ -1,6 +1,14 @@
+#[cfg(feature = "dashmap-6")]
+pub(crate) mod dashmap;
+#[cfg(feature = "rand-09")]
+mod rand;
 #[cfg(feature = "rayon-1")]
 mod rayon;
 #[cfg(feature = "rkyv-08")]
-mod rkyv;
+pub(crate) mod rkyv;
 #[cfg(feature = "scylla-1")]
 mod scylla;
+#[cfg(feature = "serde-1")]
+pub(crate) mod serde;
+#[cfg(feature = "speedy-08")]
+pub(crate) mod speedy;
@@ -0,0 +1,105 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use dashmap::DashMap;
+use uuid::Uuid;
+
+use crate::UuidBuildHasher;
+
+/// A concurrent [`UuidMap`][1], backed by a [`DashMap`].
+///
+/// This uses [`UuidHasher`][2] as the hasher, so that the random bits of UUIDv4s and
+/// UUIDv7s are used instead of hashing them. Like [`UuidMap`][1], this will panic if
+/// used with UUIDs [`UuidHasher`][2] doesn't support.
+///
+/// [1]: crate::UuidMap
+/// [2]: crate::UuidHasher
+pub struct UuidDashMap<V>(DashMap<Uuid, V, UuidBuildHasher>);
+
+impl<V> UuidDashMap<V> {
+    /// Creates an empty [`UuidDashMap`].
+    ///
+    /// See [`DashMap::new()`].
+    #[inline]
+    pub fn new() -> Self {
+        Self(DashMap::with_hasher(UuidBuildHasher))
+    }
+
+    /// Creates an empty [`UuidDashMap`] with at least the specified capacity.
+    ///
+    /// See [`DashMap::with_capacity()`].
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(DashMap::with_capacity_and_hasher(capacity, UuidBuildHasher))
+    }
+
+    /// Creates an empty [`UuidDashMap`] split into the specified amount of shards.
+    ///
+    /// See [`DashMap::with_shard_amount()`].
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if `shard_amount` isn't a power of two greater than 1.
+    #[inline]
+    pub fn with_shard_amount(shard_amount: usize) -> Self {
+        Self(DashMap::with_hasher_and_shard_amount(
+            UuidBuildHasher,
+            shard_amount,
+        ))
+    }
+
+    /// Converts the [`UuidDashMap`] into the [`DashMap`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> DashMap<Uuid, V, UuidBuildHasher> {
+        self.0
+    }
+}
+
+impl<V> Default for UuidDashMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> From<DashMap<Uuid, V, UuidBuildHasher>> for UuidDashMap<V> {
+    #[inline]
+    fn from(map: DashMap<Uuid, V, UuidBuildHasher>) -> Self {
+        Self(map)
+    }
+}
+
+impl<V> From<UuidDashMap<V>> for DashMap<Uuid, V, UuidBuildHasher> {
+    #[inline]
+    fn from(map: UuidDashMap<V>) -> Self {
+        map.0
+    }
+}
+
+impl<V> Deref for UuidDashMap<V> {
+    type Target = DashMap<Uuid, V, UuidBuildHasher>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<V: Debug> Debug for UuidDashMap<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<V> FromIterator<(Uuid, V)> for UuidDashMap<V> {
+    fn from_iter<T: IntoIterator<Item = (Uuid, V)>>(iter: T) -> Self {
+        let map = Self::new();
+        for (uuid, value) in iter {
+            map.insert(uuid, value);
+        }
+
+        map
+    }
+}
@@ -0,0 +1,71 @@
+use rand::Rng;
+use uuid::Uuid;
+
+#[cfg(feature = "rkyv-08")]
+use super::rkyv::ArchivedUuidMap;
+#[cfg(feature = "rkyv-08")]
+use crate::UuidMap;
+use crate::UuidSet;
+
+impl UuidSet {
+    /// Returns `k` UUIDs chosen uniformly at random from the set, or all of them if
+    /// it contains less than `k` UUIDs.
+    ///
+    /// This uses reservoir sampling, visiting the set only once without cloning it.
+    /// The order of the returned UUIDs is random.
+    pub fn sample_k<R: Rng + ?Sized>(&self, k: usize, rng: &mut R) -> Vec<Uuid> {
+        sample(self.iter().copied(), self.len(), k, rng)
+    }
+}
+
+#[cfg(feature = "rkyv-08")]
+impl<V> UuidMap<V> {
+    /// Returns whether the map and the given archived map have the same length and
+    /// agree on the values of `k` UUIDs chosen uniformly at random from the map.
+    ///
+    /// This is a cheap, probabilistic alternative to comparing all the entries of
+    /// both maps: a single diverging entry is detected with probability `k / n`.
+    pub fn sample_agrees_with_archived<AV, R>(
+        &self,
+        archived: &ArchivedUuidMap<AV>,
+        k: usize,
+        rng: &mut R,
+    ) -> bool
+    where
+        AV: PartialEq<V>,
+        R: Rng + ?Sized,
+    {
+        if self.len() != archived.len() {
+            return false;
+        }
+
+        sample(self.iter(), self.len(), k, rng)
+            .into_iter()
+            .all(|(uuid, value)| archived.get(uuid).is_some_and(|archived| archived == value))
+    }
+}
+
+/// Returns `k` items chosen uniformly at random from the `len` items of `iter`, or
+/// all of them if there are less than `k`, using reservoir sampling.
+fn sample<T, R: Rng + ?Sized>(
+    iter: impl Iterator<Item = T>,
+    len: usize,
+    k: usize,
+    rng: &mut R,
+) -> Vec<T> {
+    let mut reservoir = Vec::with_capacity(k.min(len));
+
+    for (idx, item) in iter.enumerate() {
+        if idx < k {
+            reservoir.push(item);
+            continue;
+        }
+
+        let pick = rng.random_range(0..=idx);
+        if pick < k {
+            reservoir[pick] = item;
+        }
+    }
+
+    reservoir
+}
@@ -1,15 +1,39 @@
 use std::collections::{HashMap, HashSet};
 
 use indexmap::{IndexMap, IndexSet};
-use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend};
+use rayon::iter::{FromParallelIterator, IntoParallelIterator, ParallelExtend, ParallelIterator};
 use uuid::Uuid;
 use uuid_like::UuidLike;
 
 use crate::{
-    UuidIndexMap, UuidIndexSet, UuidLikeIndexMap, UuidLikeIndexSet, UuidLikeMap, UuidLikeSet,
-    UuidMap, UuidSet,
+    UnsupportedVersionError, UuidHasher, UuidIndexMap, UuidIndexSet, UuidLikeIndexMap,
+    UuidLikeIndexSet, UuidLikeMap, UuidLikeSet, UuidMap, UuidSet,
 };
 
+impl<V: Send> UuidMap<V> {
+    /// Creates a [`UuidMap`] from the given UUIDs, computing the value of each one by
+    /// calling `f` in parallel.
+    ///
+    /// This fails if one of the UUIDs can't be hashed by [`UuidHasher`], in which
+    /// case `f` may not be called for all the other UUIDs.
+    ///
+    /// This is the parallel counterpart of [`UuidMap::from_keys()`].
+    pub fn par_from_keys<F>(
+        keys: impl IntoParallelIterator<Item = Uuid>,
+        f: F,
+    ) -> Result<Self, UnsupportedVersionError>
+    where
+        F: Fn(&Uuid) -> V + Sync + Send,
+    {
+        keys.into_par_iter()
+            .map(|uuid| match UuidHasher::supports(&uuid) {
+                true => Ok((uuid, f(&uuid))),
+                false => Err(UnsupportedVersionError(uuid)),
+            })
+            .collect()
+    }
+}
+
 macro_rules! key {
     ($K:ident) => {
         $K
@@ -41,6 +65,24 @@ macro_rules! impl_map {
             }
         }
 
+        impl<'a, $($K: UuidLike,)? V: Sync> IntoParallelIterator for &'a $name<$($K,)? V> {
+            type Item = (&'a key!($($K)?), &'a V);
+            type Iter = <&'a $inner::<key!($($K)?), V> as IntoParallelIterator>::Iter;
+
+            fn into_par_iter(self) -> Self::Iter {
+                (&**self).into_par_iter()
+            }
+        }
+
+        impl<'a, $($K: UuidLike,)? V: Send> IntoParallelIterator for &'a mut $name<$($K,)? V> {
+            type Item = (&'a key!($($K)?), &'a mut V);
+            type Iter = <&'a mut $inner::<key!($($K)?), V> as IntoParallelIterator>::Iter;
+
+            fn into_par_iter(self) -> Self::Iter {
+                (&mut **self).into_par_iter()
+            }
+        }
+
         impl<$($K: UuidLike,)? V: Send> ParallelExtend<(key!($($K)?), V)> for $name<$($K,)? V> {
             fn par_extend<I>(&mut self, iter: I)
             where
@@ -73,6 +115,15 @@ macro_rules! impl_set {
             }
         }
 
+        impl<'a $(, $K: UuidLike)?> IntoParallelIterator for &'a $name $(<$K>)? {
+            type Item = &'a key!($($K)?);
+            type Iter = <&'a $inner::<key!($($K)?)> as IntoParallelIterator>::Iter;
+
+            fn into_par_iter(self) -> Self::Iter {
+                (&**self).into_par_iter()
+            }
+        }
+
         impl $(<$K: UuidLike>)? ParallelExtend<key!($($K)?)> for $name $(<$K>)? {
             fn par_extend<I>(&mut self, par_iter: I)
             where
@@ -1,73 +1,99 @@
+#[cfg(feature = "rkyv-08-sorted")]
+use std::vec;
 use std::{
-    fmt::{self, Debug, Formatter},
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    hash::Hash,
+    iter::FusedIterator,
     ops::Deref,
 };
 
 use rkyv::{
     Archive, Deserialize, Place, Portable, Serialize,
+    bytecheck::{CheckBytes, Verify},
     collections::swiss_table::{
         ArchivedHashMap, ArchivedHashSet, HashMapResolver, HashSetResolver,
         index_map::{ArchivedIndexMap, IndexMapResolver},
+        index_set::{ArchivedIndexSet, IndexSetResolver},
+        map,
     },
     munge::munge,
-    rancor::{Fallible, Source},
+    rancor::{Fallible, Source, fail},
     ser::{Allocator, Writer},
+    with::{ArchiveWith, DeserializeWith, SerializeWith},
 };
 use uuid::Uuid;
 use uuid_like::UuidLike;
 
 use crate::{
-    UuidHasher, UuidIndexMap, UuidLikeIndexMap, UuidLikeMap, UuidLikeSet, UuidMap, UuidSet,
+    UuidHasher, UuidIndexMap, UuidIndexSet, UuidLikeIndexMap, UuidLikeIndexSet, UuidLikeMap,
+    UuidLikeSet, UuidMap, UuidSet,
 };
 
 /// An archived [`UuidMap`].
 ///
 /// See [`ArchivedHashMap`].
-#[derive(PartialEq, Eq, Portable)]
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
 #[repr(transparent)]
-// TODO(MLB): bytecheck?
 pub struct ArchivedUuidMap<V>(ArchivedHashMap<Uuid, V, UuidHasher>);
 
 /// An archived [`UuidIndexMap`].
 ///
 /// See [`ArchivedIndexMap`].
-#[derive(PartialEq, Eq, Portable)]
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
 #[repr(transparent)]
-// TODO(MLB): bytecheck?
 pub struct ArchivedUuidIndexMap<V>(ArchivedIndexMap<Uuid, V, UuidHasher>);
 
 /// An archived [`UuidSet`].
 ///
 /// See [`ArchivedHashSet`].
-#[derive(PartialEq, Eq, Portable)]
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
 #[repr(transparent)]
-// TODO(MLB): bytecheck?
 pub struct ArchivedUuidSet(ArchivedHashSet<Uuid, UuidHasher>);
 
+/// An archived [`UuidIndexSet`].
+///
+/// See [`ArchivedIndexSet`].
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[repr(transparent)]
+pub struct ArchivedUuidIndexSet(ArchivedIndexSet<Uuid, UuidHasher>);
+
 /// An archived [`UuidLikeMap`].
 ///
 /// See [`ArchivedHashMap`].
-#[derive(PartialEq, Eq, Portable)]
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
 #[repr(transparent)]
-// TODO(MLB): bytecheck?
 pub struct ArchivedUuidLikeMap<K: UuidLike, V>(ArchivedHashMap<K, V, UuidHasher>);
 
 /// An archived [`UuidLikeIndexMap`].
 ///
 /// See [`ArchivedIndexMap`].
-#[derive(PartialEq, Eq, Portable)]
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
 #[repr(transparent)]
-// TODO(MLB): bytecheck?
 pub struct ArchivedUuidLikeIndexMap<K: UuidLike, V>(ArchivedIndexMap<K, V, UuidHasher>);
 
 /// An archived [`UuidSet`].
 ///
 /// See [`ArchivedHashSet`].
-#[derive(PartialEq, Eq, Portable)]
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
 #[repr(transparent)]
-// TODO(MLB): bytecheck?
 pub struct ArchivedUuidLikeSet<K: UuidLike>(ArchivedHashSet<K, UuidHasher>);
 
+/// An archived [`UuidLikeIndexSet`].
+///
+/// See [`ArchivedIndexSet`].
+#[derive(PartialEq, Eq, Portable, CheckBytes)]
+#[bytecheck(crate = rkyv::bytecheck, verify)]
+#[repr(transparent)]
+pub struct ArchivedUuidLikeIndexSet<K: UuidLike>(ArchivedIndexSet<K, UuidHasher>);
+
 /// The resolver for [`ArchivedUuidMap`].
 ///
 /// See [`HashMapResolver`].
@@ -83,6 +109,11 @@ pub struct UuidIndexMapResolver(IndexMapResolver);
 /// See [`HashSetResolver`].
 pub struct UuidSetResolver(HashSetResolver);
 
+/// The resolver for [`ArchivedUuidIndexSet`].
+///
+/// See [`IndexSetResolver`].
+pub struct UuidIndexSetResolver(IndexSetResolver);
+
 /// The resolver for [`ArchivedUuidLikeMap`].
 ///
 /// See [`HashMapResolver`].
@@ -98,6 +129,123 @@ pub struct UuidLikeIndexMapResolver(IndexMapResolver);
 /// See [`HashSetResolver`].
 pub struct UuidLikeSetResolver(HashSetResolver);
 
+/// The resolver for [`ArchivedUuidLikeIndexSet`].
+///
+/// See [`IndexSetResolver`].
+pub struct UuidLikeIndexSetResolver(IndexSetResolver);
+
+/// An [`ArchiveWith`] wrapper which archives a collection with a load factor of
+/// `NUM / DEN`, instead of the default of `7 / 8`.
+///
+/// A lower load factor makes archives larger, but lookups in them faster. The
+/// archived type is the same, so collections archived with a custom load factor are
+/// accessed and deserialized as usual.
+///
+/// This can be used on fields with `#[rkyv(with = LoadFactor<NUM, DEN>)]`, or with
+/// [`With::cast()`][1] to serialize a collection directly. Using it with a load
+/// factor which isn't in `(0, 1]` fails to compile.
+///
+/// [1]: rkyv::with::With::cast
+pub struct LoadFactor<const NUM: usize, const DEN: usize>;
+
+/// The load factor used when archiving collections without [`LoadFactor`].
+type DefaultLoadFactor = LoadFactor<7, 8>;
+
+impl<const NUM: usize, const DEN: usize> LoadFactor<NUM, DEN> {
+    const LOAD_FACTOR: (usize, usize) = {
+        assert!(0 < NUM && NUM <= DEN, "the load factor must be in (0, 1]");
+        (NUM, DEN)
+    };
+}
+
+/// An iterator over the entries of an [`ArchivedUuidMap`], which prefetches the
+/// entries ahead of it.
+///
+/// See [`ArchivedUuidMap::iter_prefetched()`].
+pub struct PrefetchIter<'a, V> {
+    iter: map::Iter<'a, Uuid, V, UuidHasher>,
+    ahead: map::Iter<'a, Uuid, V, UuidHasher>,
+}
+
+impl<V> ArchivedUuidMap<V> {
+    /// The number of entries prefetched ahead of the current one by
+    /// [`ArchivedUuidMap::iter_prefetched()`].
+    const PREFETCH_DISTANCE: usize = 8;
+
+    /// An iterator visiting all entries in arbitrary order, like
+    /// [`ArchivedHashMap::iter()`], which hints the CPU to prefetch the entries ahead
+    /// of the current one.
+    ///
+    /// This hides part of the memory latency of scanning archives which aren't in
+    /// the CPU cache yet (e.g. which were just memory-mapped). Prefetching is only
+    /// done on `x86_64`, and this behaves like [`ArchivedHashMap::iter()`] elsewhere.
+    pub fn iter_prefetched(&self) -> PrefetchIter<'_, V> {
+        let mut ahead = self.0.iter();
+        for (key, value) in ahead.by_ref().take(Self::PREFETCH_DISTANCE) {
+            prefetch(key);
+            prefetch(value);
+        }
+
+        PrefetchIter {
+            iter: self.0.iter(),
+            ahead,
+        }
+    }
+}
+
+impl<'a, V> Iterator for PrefetchIter<'a, V> {
+    type Item = (&'a Uuid, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((key, value)) = self.ahead.next() {
+            prefetch(key);
+            prefetch(value);
+        }
+
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for PrefetchIter<'_, V> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<V> FusedIterator for PrefetchIter<'_, V> {}
+
+/// Hints the CPU to load the cache line containing `value`.
+#[inline(always)]
+fn prefetch<T>(value: &T) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::{_MM_HINT_T0, _mm_prefetch};
+
+        // SAFETY: prefetching is only a hint, and `value` is a valid reference.
+        unsafe { _mm_prefetch::<_MM_HINT_T0>((value as *const T).cast()) };
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = value;
+}
+
+impl ArchivedUuidSet {
+    /// Returns whether the set contains the given UUID, probing the archived set in
+    /// place.
+    ///
+    /// Unlike [`ArchivedHashSet::contains()`], this returns `false` instead of
+    /// panicking if the UUID can't be hashed by [`UuidHasher`].
+    pub fn contains(&self, uuid: &Uuid) -> bool {
+        UuidHasher::supports(uuid) && self.0.contains(uuid)
+    }
+}
+
 macro_rules! key {
     ($K:ident) => {
         $K
@@ -108,32 +256,136 @@ macro_rules! key {
     };
 }
 
+/// An error returned when validating an archived collection containing a key which
+/// can't be hashed by [`UuidHasher`].
+#[derive(Debug)]
+struct UnsupportedKeyError(String);
+
+impl Display for UnsupportedKeyError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "archived key `{}` is not supported", self.0)
+    }
+}
+
+impl Error for UnsupportedKeyError {}
+
+/// Checks that all the given keys can be hashed by [`UuidHasher`], so that probing
+/// an archived collection containing them doesn't panic.
+fn verify_keys<'a, K, E>(mut keys: impl Iterator<Item = &'a K>) -> Result<(), E>
+where
+    K: Debug + Hash + 'a,
+    E: Source,
+{
+    if let Some(key) = keys.find(|key| !UuidHasher::supports_key(*key)) {
+        fail!(UnsupportedKeyError(format!("{key:?}")));
+    }
+
+    Ok(())
+}
+
+/// Returns the entries of a collection in the order they should be serialized in.
+///
+/// If the `rkyv-08-sorted` feature is enabled, the entries of hash-based collections
+/// are sorted by key, so that equal collections produce identical archives. The
+/// entries of index-based collections are always serialized in order.
+macro_rules! entries {
+    (ArchivedHashMap, $iter:expr, $key:expr) => {
+        sorted_by_key($iter, $key)
+    };
+
+    (ArchivedHashSet, $iter:expr, $key:expr) => {
+        sorted_by_key($iter, $key)
+    };
+
+    ($iarchived:ident, $iter:expr, $key:expr) => {
+        $iter
+    };
+}
+
+/// Sorts the given entries by key, if the `rkyv-08-sorted` feature is enabled.
+///
+/// The layout of an archived hash table depends on the order in which its entries
+/// are inserted, which for hash-based collections depends on their history. Sorting
+/// the entries makes it only depend on their contents, at the cost of allocating and
+/// sorting them on each serialization.
+///
+/// Entries are sorted by key rather than by hash, as different keys can have the
+/// same hash (e.g. UUIDv7s which only differ by their timestamp).
+#[cfg(feature = "rkyv-08-sorted")]
+fn sorted_by_key<T, K: Ord + ?Sized>(
+    iter: impl Iterator<Item = T>,
+    key: impl Fn(&T) -> &K,
+) -> vec::IntoIter<T> {
+    let mut entries: Vec<_> = iter.collect();
+    entries.sort_unstable_by(|a, b| key(a).cmp(key(b)));
+    entries.into_iter()
+}
+
+#[cfg(not(feature = "rkyv-08-sorted"))]
+#[inline]
+fn sorted_by_key<T, K: ?Sized, I: Iterator<Item = T>>(iter: I, _: impl Fn(&T) -> &K) -> I {
+    iter
+}
+
 macro_rules! impl_map {
     ($name:ident $(<$K:ident>)? => $archived:ident ($iarchived:ident), $resolver:ident ($iresolver:ident)) => {
         impl<$($K: UuidLike,)? V: Archive> Archive for $name<$($K,)? V> {
             type Archived = $archived<$($K,)? V::Archived>;
             type Resolver = $resolver;
 
+            #[inline]
             fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+                DefaultLoadFactor::resolve_with(self, resolver, out);
+            }
+        }
+
+        impl<$($K,)? V, S> Serialize<S> for $name<$($K,)? V>
+        where
+            $($K: UuidLike + Serialize<S> + Archive<Archived = $K>,)?
+            V: Serialize<S>,
+            S: Fallible + Writer + Allocator + ?Sized,
+            S::Error: Source,
+        {
+            #[inline]
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                DefaultLoadFactor::serialize_with(self, serializer)
+            }
+        }
+
+        impl<$($K: UuidLike,)? V: Archive, const NUM: usize, const DEN: usize>
+            ArchiveWith<$name<$($K,)? V>> for LoadFactor<NUM, DEN>
+        {
+            type Archived = $archived<$($K,)? V::Archived>;
+            type Resolver = $resolver;
+
+            fn resolve_with(
+                field: &$name<$($K,)? V>,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
                 munge!(let $archived(out) = out);
 
                 $iarchived::<_, V::Archived, UuidHasher>::resolve_from_len(
-                    self.len(),
-                    (7, 8),
+                    field.len(),
+                    Self::LOAD_FACTOR,
                     resolver.0,
                     out,
                 );
             }
         }
 
-        impl<$($K,)? V, S> Serialize<S> for $name<$($K,)? V>
+        impl<$($K,)? V, S, const NUM: usize, const DEN: usize> SerializeWith<$name<$($K,)? V>, S>
+            for LoadFactor<NUM, DEN>
         where
             $($K: UuidLike + Serialize<S> + Archive<Archived = $K>,)?
             V: Serialize<S>,
             S: Fallible + Writer + Allocator + ?Sized,
             S::Error: Source,
         {
-            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+            fn serialize_with(
+                field: &$name<$($K,)? V>,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
                 let resolver = $iarchived::<_, V::Archived, UuidHasher>::serialize_from_iter::<
                     _,
                     _,
@@ -141,12 +393,34 @@ macro_rules! impl_map {
                     key!($($K)?),
                     V,
                     _,
-                >(self.iter(), (7, 8), serializer)?;
+                >(
+                    entries!($iarchived, field.iter(), |(key, _)| key),
+                    Self::LOAD_FACTOR,
+                    serializer,
+                )?;
 
                 Ok($resolver(resolver))
             }
         }
 
+        impl<$($K,)? V, D, const NUM: usize, const DEN: usize>
+            DeserializeWith<$archived<$($K,)? V::Archived>, $name<$($K,)? V>, D>
+            for LoadFactor<NUM, DEN>
+        where
+            $($K: UuidLike + Deserialize<$K, D> + Archive<Archived = $K>,)?
+            V: Archive,
+            V::Archived: Deserialize<V, D>,
+            D: Fallible + ?Sized,
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &$archived<$($K,)? V::Archived>,
+                deserializer: &mut D,
+            ) -> Result<$name<$($K,)? V>, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+
         impl<$($K,)? V, D> Deserialize<$name<$($K,)? V>, D> for $archived<$($K,)? V::Archived>
         where
             $($K: UuidLike + Deserialize<$K, D> + Archive<Archived = $K>,)?
@@ -191,6 +465,18 @@ macro_rules! impl_map {
             }
         }
 
+        // SAFETY: this only checks that the keys of the map can be hashed by
+        // `UuidHasher`, after the map itself has been checked.
+        unsafe impl<$($K: UuidLike,)? V, C> Verify<C> for $archived<$($K,)? V>
+        where
+            C: Fallible + ?Sized,
+            C::Error: Source,
+        {
+            fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+                verify_keys(self.0.keys())
+            }
+        }
+
         impl<$($K: UuidLike,)? V> Deref for $archived<$($K,)? V> {
             type Target = $iarchived<key!($($K)?), V, UuidHasher>;
 
@@ -214,35 +500,86 @@ macro_rules! impl_set {
             type Archived = $archived$(<$K>)?;
             type Resolver = $resolver;
 
+            #[inline]
             fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+                DefaultLoadFactor::resolve_with(self, resolver, out);
+            }
+        }
+
+        impl<$($K,)? S> Serialize<S> for $name $(<$K>)?
+        where
+            $($K: UuidLike + Serialize<S> + Archive<Archived = $K>,)?
+            S: Fallible + Writer + Allocator + ?Sized,
+            S::Error: Source,
+        {
+            #[inline]
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                DefaultLoadFactor::serialize_with(self, serializer)
+            }
+        }
+
+        impl<$($K: UuidLike,)? const NUM: usize, const DEN: usize> ArchiveWith<$name $(<$K>)?>
+            for LoadFactor<NUM, DEN>
+        {
+            type Archived = $archived$(<$K>)?;
+            type Resolver = $resolver;
+
+            fn resolve_with(
+                field: &$name $(<$K>)?,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
                 munge!(let $archived(out) = out);
 
                 $iarchived::<_, UuidHasher>::resolve_from_len(
-                    self.len(),
-                    (7, 8),
+                    field.len(),
+                    Self::LOAD_FACTOR,
                     resolver.0,
                     out,
                 );
             }
         }
 
-        impl<$($K,)? S> Serialize<S> for $name $(<$K>)?
+        impl<$($K,)? S, const NUM: usize, const DEN: usize> SerializeWith<$name $(<$K>)?, S>
+            for LoadFactor<NUM, DEN>
         where
             $($K: UuidLike + Serialize<S> + Archive<Archived = $K>,)?
             S: Fallible + Writer + Allocator + ?Sized,
             S::Error: Source,
         {
-            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+            fn serialize_with(
+                field: &$name $(<$K>)?,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
                 let resolver = $iarchived::<_, UuidHasher>::serialize_from_iter::<
                     _,
                     key!($($K)?),
                     _,
-                >(self.iter(), (7, 8), serializer)?;
+                >(
+                    entries!($iarchived, field.iter(), |key| key),
+                    Self::LOAD_FACTOR,
+                    serializer,
+                )?;
 
                 Ok($resolver(resolver))
             }
         }
 
+        impl<$($K,)? D, const NUM: usize, const DEN: usize>
+            DeserializeWith<$archived $(<$K>)?, $name $(<$K>)?, D> for LoadFactor<NUM, DEN>
+        where
+            $($K: UuidLike + Deserialize<$K, D> + Archive<Archived = $K>,)?
+            D: Fallible + ?Sized,
+        {
+            #[inline]
+            fn deserialize_with(
+                field: &$archived $(<$K>)?,
+                deserializer: &mut D,
+            ) -> Result<$name $(<$K>)?, D::Error> {
+                field.deserialize(deserializer)
+            }
+        }
+
         impl<$($K,)? D> Deserialize<$name $(<$K>)?, D> for $archived $(<$K>)?
         where
             $($K: UuidLike + Deserialize<$K, D> + Archive<Archived = $K>,)?
@@ -275,6 +612,18 @@ macro_rules! impl_set {
             }
         }
 
+        // SAFETY: this only checks that the keys of the set can be hashed by
+        // `UuidHasher`, after the set itself has been checked.
+        unsafe impl<$($K: UuidLike,)? C> Verify<C> for $archived $(<$K>)?
+        where
+            C: Fallible + ?Sized,
+            C::Error: Source,
+        {
+            fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+                verify_keys(self.0.iter())
+            }
+        }
+
         impl $(<$K: UuidLike>)? Deref for $archived $(<$K>)? {
             type Target = $iarchived<key!($($K)?), UuidHasher>;
 
@@ -298,4 +647,6 @@ impl_map!(UuidLikeMap<K> => ArchivedUuidLikeMap (ArchivedHashMap), UuidLikeMapRe
 impl_map!(UuidLikeIndexMap<K> => ArchivedUuidLikeIndexMap (ArchivedIndexMap), UuidLikeIndexMapResolver (IndexMapResolver));
 
 impl_set!(UuidSet => ArchivedUuidSet (ArchivedHashSet), UuidSetResolver (HashSetResolver));
+impl_set!(UuidIndexSet => ArchivedUuidIndexSet (ArchivedIndexSet), UuidIndexSetResolver (IndexSetResolver));
 impl_set!(UuidLikeSet<K> => ArchivedUuidLikeSet (ArchivedHashSet), UuidLikeSetResolver (HashSetResolver));
+impl_set!(UuidLikeIndexSet<K> => ArchivedUuidLikeIndexSet (ArchivedIndexSet), UuidLikeIndexSetResolver (IndexSetResolver));
@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use scylla::{
     cluster::metadata::ColumnType,
-    deserialize::{DeserializationError, FrameSlice, value::DeserializeValue},
+    deserialize::{
+        DeserializationError, FrameSlice,
+        value::{DeserializeValue, ListlikeIterator, MapIterator},
+    },
     errors::TypeCheckError,
     serialize::{
         SerializationError,
@@ -13,7 +16,10 @@ use scylla::{
 use uuid::Uuid;
 use uuid_like::UuidLike;
 
-use crate::{UuidLikeMap, UuidLikeSet, UuidMap, UuidSet};
+use crate::{
+    UuidBuildHasher, UuidIndexMap, UuidIndexSet, UuidLikeIndexMap, UuidLikeIndexSet, UuidLikeMap,
+    UuidLikeSet, UuidMap, UuidSet,
+};
 
 macro_rules! key {
     ($K:ident) => {
@@ -25,6 +31,38 @@ macro_rules! key {
     };
 }
 
+/// Serializes a map, using the implementation of its inner collection if `scylla`
+/// provides one.
+macro_rules! serialize_map {
+    (HashMap, $map:expr, $typ:expr, $writer:expr) => {
+        (&**$map).serialize($typ, $writer)
+    };
+
+    // `scylla` doesn't support `IndexMap`s, and CQL maps are sorted by key, so there is
+    // no order to preserve. This allocates a temporary `HashMap` of references on each
+    // serialization, as `scylla` doesn't expose a way to serialize a map from an
+    // iterator.
+    (IndexMap, $map:expr, $typ:expr, $writer:expr) => {
+        $map.iter()
+            .collect::<HashMap<_, _, UuidBuildHasher>>()
+            .serialize($typ, $writer)
+    };
+}
+
+/// Serializes a set, using the implementation of its inner collection if `scylla`
+/// provides one.
+macro_rules! serialize_set {
+    (HashSet, $set:expr, $typ:expr, $writer:expr) => {
+        (&**$set).serialize($typ, $writer)
+    };
+
+    // `scylla` doesn't support `IndexSet`s, but supports serializing sets from
+    // sequences. This allocates a temporary `Vec` of references on each serialization.
+    (IndexSet, $set:expr, $typ:expr, $writer:expr) => {
+        $set.iter().collect::<Vec<_>>().serialize($typ, $writer)
+    };
+}
+
 macro_rules! impl_map {
     ($name:ident $(<$K:ident>)? ($inner:ident)) => {
         impl<$($K,)? V: SerializeValue> SerializeValue for $name<$($K,)? V>
@@ -36,7 +74,7 @@ macro_rules! impl_map {
                 typ: &ColumnType,
                 writer: CellWriter<'b>,
             ) -> Result<WrittenCellProof<'b>, SerializationError> {
-                (&**self).serialize(typ, writer)
+                serialize_map!($inner, self, typ, writer)
             }
         }
 
@@ -47,16 +85,17 @@ macro_rules! impl_map {
         {
             #[inline]
             fn type_check(typ: &ColumnType<'_>) -> Result<(), TypeCheckError> {
-                $inner::<key!($($K)?), V>::type_check(typ)
+                MapIterator::<'frame, 'metadata, key!($($K)?), V>::type_check(typ)
             }
 
+            // This collects the entries in the order they are received in, which for
+            // CQL maps is sorted by key.
             #[inline]
             fn deserialize(
                 typ: &'metadata ColumnType<'metadata>,
                 v: Option<FrameSlice<'frame>>,
             ) -> Result<Self, DeserializationError> {
-                let inner = $inner::<key!($($K)?), V, _>::deserialize(typ, v)?;
-                Ok(Self::from(inner))
+                MapIterator::<'frame, 'metadata, key!($($K)?), V>::deserialize(typ, v)?.collect()
             }
         }
     };
@@ -73,7 +112,7 @@ macro_rules! impl_set {
                 typ: &ColumnType,
                 writer: CellWriter<'b>,
             ) -> Result<WrittenCellProof<'b>, SerializationError> {
-                (&**self).serialize(typ, writer)
+                serialize_set!($inner, self, typ, writer)
             }
         }
 
@@ -82,25 +121,28 @@ macro_rules! impl_set {
         {
             #[inline]
             fn type_check(typ: &ColumnType<'_>) -> Result<(), TypeCheckError> {
-                $inner::<key!($($K)?)>::type_check(typ)
+                ListlikeIterator::<'frame, 'metadata, key!($($K)?)>::type_check(typ)
             }
 
+            // This collects the UUIDs in the order they are received in, which for CQL
+            // sets is sorted.
             #[inline]
             fn deserialize(
                 typ: &'metadata ColumnType<'metadata>,
                 v: Option<FrameSlice<'frame>>,
             ) -> Result<Self, DeserializationError> {
-                let inner = $inner::<key!($($K)?), _>::deserialize(typ, v)?;
-                Ok(Self::from(inner))
+                ListlikeIterator::<'frame, 'metadata, key!($($K)?)>::deserialize(typ, v)?.collect()
             }
         }
     };
 }
 
 impl_map!(UuidMap(HashMap));
+impl_map!(UuidIndexMap(IndexMap));
 impl_map!(UuidLikeMap<K> (HashMap));
-// TODO(MLB): `UuidIndexMap` and `UuidLikeIndexMap`
+impl_map!(UuidLikeIndexMap<K> (IndexMap));
 
 impl_set!(UuidSet(HashSet));
+impl_set!(UuidIndexSet(IndexSet));
 impl_set!(UuidLikeSet<K> (HashSet));
-// TODO(MLB): `UuidIndexSet` and `UuidLikeIndexSet`
+impl_set!(UuidLikeIndexSet<K> (IndexSet));
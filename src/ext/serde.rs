@@ -0,0 +1,253 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{Error, MapAccess, SeqAccess, Visitor},
+};
+use uuid::Uuid;
+use uuid_like::UuidLike;
+
+use crate::{
+    UuidHasher, UuidIndexMap, UuidIndexSet, UuidLikeIndexMap, UuidLikeIndexSet, UuidLikeMap,
+    UuidLikeSet, UuidMap, UuidSet,
+};
+
+/// Implements [`Deserialize`] for a map, failing instead of panicking if one of its
+/// keys can't be hashed by [`UuidHasher`].
+macro_rules! impl_map {
+    ($name:ident $(<$K:ident>)?) => {
+        impl<'de, $($K: UuidLike + Deserialize<'de>,)? V: Deserialize<'de>> Deserialize<'de>
+            for $name<$($K,)? V>
+        {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct MapVisitor<$($K,)? V>(PhantomData<($($K,)? V,)>);
+
+                impl<'de, $($K: UuidLike + Deserialize<'de>,)? V: Deserialize<'de>> Visitor<'de>
+                    for MapVisitor<$($K,)? V>
+                {
+                    type Value = $name<$($K,)? V>;
+
+                    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                        f.write_str("a map of UUIDs")
+                    }
+
+                    fn visit_newtype_struct<D: Deserializer<'de>>(
+                        self,
+                        deserializer: D,
+                    ) -> Result<Self::Value, D::Error> {
+                        deserializer.deserialize_map(self)
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(
+                        self,
+                        mut access: A,
+                    ) -> Result<Self::Value, A::Error> {
+                        let mut map = $name::with_capacity(access.size_hint().unwrap_or(0));
+
+                        while let Some(key) = access.next_key()? {
+                            let key = supported(key)?;
+                            map.insert(key, access.next_value()?);
+                        }
+
+                        Ok(map)
+                    }
+                }
+
+                deserializer.deserialize_newtype_struct(stringify!($name), MapVisitor(PhantomData))
+            }
+        }
+    };
+}
+
+/// Implements [`Deserialize`] for a set, failing instead of panicking if one of its
+/// keys can't be hashed by [`UuidHasher`].
+macro_rules! impl_set {
+    ($name:ident $(<$K:ident>)?) => {
+        impl<'de, $($K: UuidLike + Deserialize<'de>)?> Deserialize<'de> for $name$(<$K>)? {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct SetVisitor$(<$K>(PhantomData<$K>))?;
+
+                impl<'de, $($K: UuidLike + Deserialize<'de>)?> Visitor<'de> for SetVisitor$(<$K>)? {
+                    type Value = $name$(<$K>)?;
+
+                    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                        f.write_str("a sequence of UUIDs")
+                    }
+
+                    fn visit_newtype_struct<D: Deserializer<'de>>(
+                        self,
+                        deserializer: D,
+                    ) -> Result<Self::Value, D::Error> {
+                        deserializer.deserialize_seq(self)
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(
+                        self,
+                        mut access: A,
+                    ) -> Result<Self::Value, A::Error> {
+                        let mut set = $name::with_capacity(access.size_hint().unwrap_or(0));
+
+                        while let Some(key) = access.next_element()? {
+                            set.insert(supported(key)?);
+                        }
+
+                        Ok(set)
+                    }
+                }
+
+                deserializer.deserialize_newtype_struct(
+                    stringify!($name),
+                    SetVisitor$(::<$K>(PhantomData))?,
+                )
+            }
+        }
+    };
+}
+
+impl_map!(UuidMap);
+impl_map!(UuidIndexMap);
+impl_map!(UuidLikeMap<K>);
+impl_map!(UuidLikeIndexMap<K>);
+
+impl_set!(UuidSet);
+impl_set!(UuidIndexSet);
+impl_set!(UuidLikeSet<K>);
+impl_set!(UuidLikeIndexSet<K>);
+
+/// A wrapper around a [`UuidMap`] which fails to deserialize if its input contains
+/// the same UUID more than once, instead of keeping the last value.
+///
+/// This depends on the data format passing duplicate keys to the deserializer:
+/// some formats (or parsers) drop them before they can be detected.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DenyDuplicateKeys<V>(pub UuidMap<V>);
+
+impl<V> From<DenyDuplicateKeys<V>> for UuidMap<V> {
+    #[inline]
+    fn from(map: DenyDuplicateKeys<V>) -> Self {
+        map.0
+    }
+}
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for DenyDuplicateKeys<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MapVisitor<V>(PhantomData<V>);
+
+        impl<'de, V: Deserialize<'de>> Visitor<'de> for MapVisitor<V> {
+            type Value = UuidMap<V>;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("a map without duplicate UUIDs")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = UuidMap::with_capacity(access.size_hint().unwrap_or(0));
+
+                while let Some(uuid) = access.next_key::<Uuid>()? {
+                    let uuid = supported(uuid)?;
+                    if map.insert(uuid, access.next_value()?).is_some() {
+                        return Err(A::Error::custom(format_args!("duplicate UUID `{uuid}`")));
+                    }
+                }
+
+                Ok(map)
+            }
+        }
+
+        let map = deserializer.deserialize_map(MapVisitor(PhantomData))?;
+        Ok(Self(map))
+    }
+}
+
+/// Deserializes a [`UuidMap`], failing if any of its UUIDs isn't a UUIDv7.
+///
+/// This is meant to be used with `#[serde(deserialize_with = "...")]`.
+pub fn deserialize_all_v7<'de, D, V>(deserializer: D) -> Result<UuidMap<V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let map = UuidMap::<V>::deserialize(deserializer)?;
+
+    if let Some(uuid) = map.keys().find(|uuid| uuid.get_version_num() != 7) {
+        return Err(D::Error::custom(format_args!(
+            "UUID `{uuid}` is not a UUIDv7"
+        )));
+    }
+
+    Ok(map)
+}
+
+/// Serializes a map as a sequence of `(key, value)` pairs instead of a map.
+///
+/// By default, maps are serialized as maps and sets as sequences. This is meant to
+/// be used with `#[serde(serialize_with = "...")]` for maps whose keys can't be
+/// serialized as the keys of a map by some formats (e.g. keys which don't serialize
+/// to strings, with JSON).
+///
+/// See [`deserialize_from_seq()`].
+pub fn serialize_as_seq<'a, S, K, V>(
+    map: impl IntoIterator<Item = (&'a K, &'a V)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + 'a,
+    V: Serialize + 'a,
+{
+    serializer.collect_seq(map)
+}
+
+/// Deserializes a map from a sequence of `(key, value)` pairs, failing if one of its
+/// keys can't be hashed by [`UuidHasher`].
+///
+/// This is meant to be used with `#[serde(deserialize_with = "...")]`.
+///
+/// See [`serialize_as_seq()`].
+pub fn deserialize_from_seq<'de, D, M, K, V>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: Default + Extend<(K, V)>,
+    K: Debug + Hash + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    struct SeqVisitor<M, K, V>(PhantomData<(M, K, V)>);
+
+    impl<'de, M, K, V> Visitor<'de> for SeqVisitor<M, K, V>
+    where
+        M: Default + Extend<(K, V)>,
+        K: Debug + Hash + Deserialize<'de>,
+        V: Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+            f.write_str("a sequence of (UUID, value) pairs")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+            let mut map = M::default();
+
+            while let Some((key, value)) = access.next_element::<(K, V)>()? {
+                map.extend([(supported(key)?, value)]);
+            }
+
+            Ok(map)
+        }
+    }
+
+    deserializer.deserialize_seq(SeqVisitor(PhantomData))
+}
+
+/// Returns the given key, or an error if it can't be hashed by [`UuidHasher`].
+fn supported<K: Debug + Hash, E: Error>(key: K) -> Result<K, E> {
+    if !UuidHasher::supports_key(&key) {
+        return Err(E::custom(format_args!("UUID `{key:?}` is not supported")));
+    }
+
+    Ok(key)
+}
@@ -0,0 +1,390 @@
+use std::{
+    hash::Hash,
+    io::{self, Read, Write},
+    ops::Deref,
+    time::SystemTime,
+};
+
+use speedy::{Context, IsEof, LittleEndian, Readable, Reader, Writable, Writer};
+use uuid::Uuid;
+use uuid_like::UuidLike;
+
+use crate::{
+    UuidHasher, UuidIndexMap, UuidIndexSet, UuidLikeIndexMap, UuidLikeIndexSet, UuidLikeMap,
+    UuidLikeSet, UuidMap, UuidSet, v7_time,
+};
+
+macro_rules! key {
+    ($K:ident) => {
+        $K
+    };
+
+    () => {
+        Uuid
+    };
+}
+
+/// Reads the length prefix of a collection.
+fn read_len<'a, C: Context, R: Reader<'a, C>>(reader: &mut R) -> Result<usize, C::Error> {
+    Ok(reader.read_u32()? as usize)
+}
+
+/// Writes the length prefix of a collection.
+fn write_len<C: Context, W: ?Sized + Writer<C>>(
+    len: usize,
+    writer: &mut W,
+) -> Result<(), C::Error> {
+    let len = u32::try_from(len)
+        .map_err(|_| speedy::Error::custom("collection is too long to be written"))?;
+
+    writer.write_u32(len)
+}
+
+/// Reads a key, failing if it can't be hashed by [`UuidHasher`].
+fn read_key<'a, C, R, K>(reader: &mut R) -> Result<K, C::Error>
+where
+    C: Context,
+    R: Reader<'a, C>,
+    K: Readable<'a, C> + Hash,
+{
+    let key = K::read_from(reader)?;
+
+    if !UuidHasher::supports_key(&key) {
+        return Err(speedy::Error::custom("UUID version is not supported").into());
+    }
+
+    Ok(key)
+}
+
+impl<V> UuidMap<V> {
+    /// Writes the entries whose UUID is a UUIDv7 with an embedded timestamp after
+    /// `since` to `stream`, e.g. to back up the entries added since the last backup.
+    ///
+    /// The entries are written as a single length-prefixed frame, using the same
+    /// format as [`UuidMap`]'s [`Writable`] implementation. Entries whose UUID is of
+    /// another version aren't written.
+    ///
+    /// See [`UuidMap::merge_window()`].
+    pub fn serialize_window<W: Write>(
+        &self,
+        since: SystemTime,
+        stream: W,
+    ) -> Result<(), speedy::Error>
+    where
+        V: Writable<LittleEndian>,
+    {
+        let window = Window { map: self, since };
+        window.write_to_stream_with_ctx(LittleEndian::default(), stream)
+    }
+
+    /// Reads a frame of entries written by [`UuidMap::serialize_window()`] from
+    /// `stream`, and inserts them into the map.
+    ///
+    /// This doesn't read past the end of the frame, so that several frames can be
+    /// read from the same stream. The entries of the frame replace the ones of the
+    /// map with the same UUID.
+    pub fn merge_window<R: Read>(&mut self, stream: R) -> Result<(), speedy::Error>
+    where
+        V: for<'a> Readable<'a, LittleEndian>,
+    {
+        let window = Self::read_from_stream_unbuffered_with_ctx(LittleEndian::default(), stream)?;
+        self.extend(window);
+
+        Ok(())
+    }
+}
+
+/// The entries of a [`UuidMap`] whose UUID is a UUIDv7 with an embedded timestamp
+/// after `since`.
+struct Window<'a, V> {
+    map: &'a UuidMap<V>,
+    since: SystemTime,
+}
+
+impl<C: Context, V: Writable<C>> Writable<C> for Window<'_, V> {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        write_len(self.map.count_newer_than(self.since), writer)?;
+
+        for (uuid, value) in self.map.iter() {
+            if v7_time(uuid).is_some_and(|time| time > self.since) {
+                uuid.write_to(writer)?;
+                value.write_to(writer)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`UuidMap`] which writes its insertions and removals to an append-only log,
+/// so that it can be rebuilt after a crash with [`LoggedUuidMap::recover_from_log()`].
+///
+/// Each operation is written to the log as a record, before being applied to the
+/// map. The log isn't flushed after each operation, so buffered writers must be
+/// flushed with [`LoggedUuidMap::flush()`] for the operations to be durable.
+pub struct LoggedUuidMap<V, W: Write> {
+    map: UuidMap<V>,
+    log: W,
+}
+
+/// A record of a [`LoggedUuidMap`]'s log, inserting a value if there is one or
+/// removing the UUID otherwise.
+struct Record<'a, V> {
+    uuid: &'a Uuid,
+    value: Option<&'a V>,
+}
+
+/// The tag of records inserting a value.
+const INSERT: u8 = 0;
+
+/// The tag of records removing a UUID.
+const REMOVE: u8 = 1;
+
+impl<V, W: Write> LoggedUuidMap<V, W> {
+    /// Creates an empty [`LoggedUuidMap`], writing its operations to `log`.
+    #[inline]
+    pub fn new(log: W) -> Self {
+        Self {
+            map: UuidMap::new(),
+            log,
+        }
+    }
+
+    /// Rebuilds a [`LoggedUuidMap`] by replaying the records read from `stream`, and
+    /// writes its next operations to `log`.
+    ///
+    /// A truncated last record, e.g. left by a crash while it was being written, is
+    /// ignored. This fails if a record is invalid or its UUID can't be hashed by
+    /// [`UuidHasher`].
+    pub fn recover_from_log<R: Read>(mut stream: R, log: W) -> Result<Self, speedy::Error>
+    where
+        V: for<'a> Readable<'a, LittleEndian>,
+    {
+        let mut map = UuidMap::new();
+
+        while let Some(record) = read_record(&mut stream)? {
+            match record.value {
+                Some(value) => map.insert(record.uuid, value),
+                None => map.remove(&record.uuid),
+            };
+        }
+
+        Ok(Self { map, log })
+    }
+
+    /// Inserts a value into the map after writing the insertion to the log, and
+    /// returns the previous value of the UUID, if any.
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if the UUID can't be hashed by [`UuidHasher`], before writing
+    /// to the log.
+    pub fn insert(&mut self, uuid: Uuid, value: V) -> Result<Option<V>, speedy::Error>
+    where
+        V: Writable<LittleEndian>,
+    {
+        assert!(
+            UuidHasher::supports(&uuid),
+            "UUID `{uuid}` is not supported"
+        );
+
+        let record = Record {
+            uuid: &uuid,
+            value: Some(&value),
+        };
+
+        record.write_to_stream_with_ctx(LittleEndian::default(), &mut self.log)?;
+        Ok(self.map.insert(uuid, value))
+    }
+
+    /// Removes a UUID from the map after writing the removal to the log, and returns
+    /// its value, if any.
+    ///
+    /// Nothing is written to the log if the UUID isn't in the map.
+    pub fn remove(&mut self, uuid: &Uuid) -> Result<Option<V>, speedy::Error>
+    where
+        V: Writable<LittleEndian>,
+    {
+        if !self.map.contains_key(uuid) {
+            return Ok(None);
+        }
+
+        let record = Record::<V> { uuid, value: None };
+        record.write_to_stream_with_ctx(LittleEndian::default(), &mut self.log)?;
+
+        Ok(self.map.remove(uuid))
+    }
+
+    /// Flushes the log.
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.log.flush()
+    }
+
+    /// Converts the [`LoggedUuidMap`] into its map and its log.
+    #[inline]
+    pub fn into_parts(self) -> (UuidMap<V>, W) {
+        (self.map, self.log)
+    }
+}
+
+impl<V, W: Write> Deref for LoggedUuidMap<V, W> {
+    type Target = UuidMap<V>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<C: Context, V: Writable<C>> Writable<C> for Record<'_, V> {
+    fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        match self.value {
+            Some(value) => {
+                writer.write_u8(INSERT)?;
+                self.uuid.write_to(writer)?;
+                value.write_to(writer)
+            }
+
+            None => {
+                writer.write_u8(REMOVE)?;
+                self.uuid.write_to(writer)
+            }
+        }
+    }
+}
+
+/// A record read from a [`LoggedUuidMap`]'s log.
+///
+/// See [`Record`].
+struct OwnedRecord<V> {
+    uuid: Uuid,
+    value: Option<V>,
+}
+
+impl<'a, C: Context, V: Readable<'a, C>> Readable<'a, C> for OwnedRecord<V> {
+    fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        let tag = reader.read_u8()?;
+        let uuid = read_key(reader)?;
+
+        let value = match tag {
+            INSERT => Some(V::read_from(reader)?),
+            REMOVE => None,
+            _ => return Err(speedy::Error::custom("invalid log record").into()),
+        };
+
+        Ok(Self { uuid, value })
+    }
+}
+
+/// Reads the next record of a [`LoggedUuidMap`]'s log, returning `None` at the end
+/// of the log or if the record is truncated.
+fn read_record<V, R: Read>(stream: &mut R) -> Result<Option<OwnedRecord<V>>, speedy::Error>
+where
+    V: for<'a> Readable<'a, LittleEndian>,
+{
+    match OwnedRecord::read_from_stream_unbuffered_with_ctx(LittleEndian::default(), stream) {
+        Ok(record) => Ok(Some(record)),
+        Err(error) if error.is_eof() => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+macro_rules! impl_map {
+    ($name:ident $(<$K:ident>)?) => {
+        impl<'a, C, $($K,)? V> Readable<'a, C> for $name<$($K,)? V>
+        where
+            C: Context,
+            $($K: UuidLike + Readable<'a, C>,)?
+            V: Readable<'a, C>,
+        {
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let len = read_len(reader)?;
+                let mut map = Self::new();
+
+                for _ in 0..len {
+                    let key = read_key::<_, _, key!($($K)?)>(reader)?;
+                    let value = V::read_from(reader)?;
+                    map.insert(key, value);
+                }
+
+                Ok(map)
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                4
+            }
+        }
+
+        impl<C, $($K,)? V> Writable<C> for $name<$($K,)? V>
+        where
+            C: Context,
+            $($K: UuidLike + Writable<C>,)?
+            V: Writable<C>,
+        {
+            fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+                write_len(self.len(), writer)?;
+
+                for (key, value) in self.iter() {
+                    key.write_to(writer)?;
+                    value.write_to(writer)?;
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! impl_set {
+    ($name:ident $(<$K:ident>)?) => {
+        impl<'a, C $(, $K)?> Readable<'a, C> for $name $(<$K>)?
+        where
+            C: Context,
+            $($K: UuidLike + Readable<'a, C>,)?
+        {
+            fn read_from<R: Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+                let len = read_len(reader)?;
+                let mut set = Self::new();
+
+                for _ in 0..len {
+                    set.insert(read_key::<_, _, key!($($K)?)>(reader)?);
+                }
+
+                Ok(set)
+            }
+
+            #[inline]
+            fn minimum_bytes_needed() -> usize {
+                4
+            }
+        }
+
+        impl<C $(, $K)?> Writable<C> for $name $(<$K>)?
+        where
+            C: Context,
+            $($K: UuidLike + Writable<C>,)?
+        {
+            fn write_to<W: ?Sized + Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+                write_len(self.len(), writer)?;
+
+                for key in self.iter() {
+                    key.write_to(writer)?;
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_map!(UuidMap);
+impl_map!(UuidLikeMap<K>);
+impl_map!(UuidIndexMap);
+impl_map!(UuidLikeIndexMap<K>);
+
+impl_set!(UuidSet);
+impl_set!(UuidLikeSet<K>);
+impl_set!(UuidIndexSet);
+impl_set!(UuidLikeIndexSet<K>);
@@ -1,4 +1,13 @@
-use std::hash::{BuildHasher, Hasher};
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, DefaultHasher, Hash, Hasher},
+    marker::PhantomData,
+    num::{NonZeroU64, NonZeroUsize},
+};
+
+use uuid::Uuid;
+
+use crate::UuidKey;
 
 /// A [`BuildHasher`] that builds [`UuidHasher`]s, which use the random bits of
 /// UUIDv4s and UUIDv7s instead of hashing them.
@@ -11,15 +20,88 @@ pub struct UuidBuildHasher;
 /// A [`Hasher`] which uses the random bits of UUIDv4s and UUIDv7s instead of
 /// hashing them.
 ///
+/// UUIDv6s are also supported, using their clock sequence and node ID mixed with
+/// the low bits of their timestamp.
+///
 /// If the `gxhash-3` feature is enabled, then [`gxhash`] is used as a fallback to
-/// allow this hasher to be used with other UUID versions.
+/// allow this hasher to be used with other UUID versions. UUIDv4s, UUIDv6s and
+/// UUIDv7s are still hashed using their bits as described above.
+///
+/// Keys made of several UUIDs (e.g. `(Uuid, Uuid)` or `[Uuid; 3]`) are supported,
+/// by mixing the hash of each UUID into the hash of the previous ones. The hash of a
+/// single UUID isn't mixed.
 ///
 /// ## Panics
 ///
-/// This will panic if used with something other than a UUIDv4 or a UUIDv7.
+/// This will panic if used with something other than UUIDv4s, UUIDv6s or UUIDv7s.
 #[derive(Default)]
 pub struct UuidHasher {
     hash: u64,
+    written: bool,
+    /// The number of UUIDs announced by the length of a slice which haven't been
+    /// written yet.
+    pending: usize,
+}
+
+/// A [`BuildHasher`] that builds [`FallbackUuidHasher`]s, which hash all the bits of
+/// UUIDs instead of only using their random bits.
+#[derive(Clone, Copy, Default)]
+pub struct FallbackUuidBuildHasher;
+
+/// A [`Hasher`] which hashes all the bits of UUIDs using [`DefaultHasher`].
+///
+/// This is slower than [`UuidHasher`], but doesn't produce collisions for UUIDs
+/// which only differ by their non-random bits.
+#[derive(Default)]
+pub struct FallbackUuidHasher(DefaultHasher);
+
+/// A [`BuildHasher`] that builds [`FoldingUuidHasher`]s, which fold all the bits of
+/// UUIDs except their version and variant into their hash.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct FoldingUuidBuildHasher;
+
+/// A [`Hasher`] which folds all the bits of UUIDs except their version and variant
+/// (i.e. all the 122 random bits of UUIDv4s) into their hash, using a cheap mix.
+///
+/// This is slightly slower than [`UuidHasher`], but doesn't produce collisions for
+/// UUIDs which only differ by the bits [`UuidHasher`] doesn't use (e.g. UUIDv7s
+/// which only differ by their timestamp).
+///
+/// ## Panics
+///
+/// This will panic if used with something other than a UUID.
+#[derive(Default)]
+pub struct FoldingUuidHasher {
+    hash: u64,
+}
+
+/// A [`BuildHasher`] that builds [`UuidOrHashHasher`]s, which use the random bits of
+/// UUIDv4s and UUIDv7s and hash other UUIDs using `H`.
+pub struct UuidOrHashBuildHasher<H = DefaultHasher>(PhantomData<fn() -> H>);
+
+/// A [`Hasher`] which uses the random bits of UUIDv4s and UUIDv7s instead of hashing
+/// them, and hashes other UUIDs using `H`.
+///
+/// Unlike [`UuidHasher`], this never panics. UUIDs of other versions are hashed in
+/// full, so that UUIDs which only differ by their non-random bits don't collide.
+#[derive(Default)]
+pub struct UuidOrHashHasher<H = DefaultHasher> {
+    state: UuidOrHashState,
+    fallback: H,
+}
+
+/// The state of a [`UuidOrHashHasher`].
+#[derive(Default)]
+enum UuidOrHashState {
+    /// Nothing was written yet.
+    #[default]
+    Empty,
+
+    /// A single UUIDv4 or UUIDv7 was written, and this is its hash.
+    Random(u64),
+
+    /// Something else was written to the fallback hasher.
+    Fallback,
 }
 
 impl BuildHasher for UuidBuildHasher {
@@ -30,6 +112,160 @@ impl BuildHasher for UuidBuildHasher {
     }
 }
 
+impl BuildHasher for FallbackUuidBuildHasher {
+    type Hasher = FallbackUuidHasher;
+
+    fn build_hasher(&self) -> FallbackUuidHasher {
+        FallbackUuidHasher::default()
+    }
+}
+
+impl BuildHasher for FoldingUuidBuildHasher {
+    type Hasher = FoldingUuidHasher;
+
+    fn build_hasher(&self) -> FoldingUuidHasher {
+        FoldingUuidHasher::default()
+    }
+}
+
+impl<H: Hasher + Default> BuildHasher for UuidOrHashBuildHasher<H> {
+    type Hasher = UuidOrHashHasher<H>;
+
+    fn build_hasher(&self) -> UuidOrHashHasher<H> {
+        UuidOrHashHasher::default()
+    }
+}
+
+impl<H> Default for UuidOrHashBuildHasher<H> {
+    #[inline]
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<H> Clone for UuidOrHashBuildHasher<H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H> Copy for UuidOrHashBuildHasher<H> {}
+
+impl<H> Debug for UuidOrHashBuildHasher<H> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("UuidOrHashBuildHasher")
+    }
+}
+
+impl UuidBuildHasher {
+    /// Returns the index of the shard the given UUID belongs to, out of `shards`.
+    ///
+    /// This is derived from the hash of the UUID, so that UUIDs are spread evenly
+    /// across shards.
+    pub fn shard_of(&self, uuid: &Uuid, shards: NonZeroUsize) -> usize {
+        (self.hash_one(uuid) % shards.get() as u64) as usize
+    }
+
+    /// Returns the position of the given UUID on a consistent-hashing ring with
+    /// `ring_size` positions, in `0..ring_size`.
+    ///
+    /// This is derived from the hash of the UUID, so that UUIDs are spread evenly
+    /// across the ring.
+    pub fn ring_position(&self, uuid: &Uuid, ring_size: NonZeroU64) -> u64 {
+        // UUIDv7s generated within the same millisecond may only differ by a counter
+        // in their first random bits, so the hash is avalanched before being scaled
+        // down to the ring.
+        let hash = avalanche(self.hash_one(uuid));
+        ((hash as u128 * ring_size.get() as u128) >> 64) as u64
+    }
+}
+
+impl UuidHasher {
+    /// Returns whether the given UUID can be hashed by [`UuidHasher`] without panicking.
+    pub fn supports(uuid: &Uuid) -> bool {
+        let version = uuid.version();
+
+        #[cfg(feature = "gxhash-3")]
+        if version != 4 && version != 6 && version != 7 {
+            return true;
+        }
+
+        #[cfg(not(feature = "gxhash-3"))]
+        if version != 4 && version != 6 && version != 7 {
+            return false;
+        }
+
+        let variant = (uuid.as_bytes()[8] & 0b11000000) >> 6;
+        variant == 2
+    }
+
+    /// Returns whether the given key can be hashed by [`UuidHasher`] without
+    /// panicking.
+    ///
+    /// This is [`UuidHasher::supports()`] for keys which hash like a UUID (e.g. which
+    /// implement [`UuidLike`][1]).
+    ///
+    /// [1]: uuid_like::UuidLike
+    pub fn supports_key<K: Hash + ?Sized>(key: &K) -> bool {
+        /// A [`Hasher`] recording the UUID it is given.
+        #[derive(Default)]
+        struct Recorder {
+            uuid: Option<Uuid>,
+            writes: usize,
+        }
+
+        impl Hasher for Recorder {
+            fn finish(&self) -> u64 {
+                0
+            }
+
+            fn write(&mut self, bytes: &[u8]) {
+                self.uuid = Uuid::from_slice(bytes).ok();
+                self.writes += 1;
+            }
+        }
+
+        let mut recorder = Recorder::default();
+        key.hash(&mut recorder);
+
+        match recorder.uuid {
+            Some(uuid) if recorder.writes == 1 => Self::supports(&uuid),
+            _ => false,
+        }
+    }
+
+    /// Mixes the hash of a UUID into the hash of the previously written ones, if
+    /// there were any.
+    #[inline]
+    fn combine(&mut self, hash: u64) {
+        self.hash = match self.written {
+            true => avalanche(self.hash) ^ hash,
+            false => hash,
+        };
+
+        self.written = true;
+        self.pending = self.pending.saturating_sub(1);
+    }
+}
+
+/// Returns whether the given UUID is a UUIDv4 or a UUIDv7, whose random bits can be
+/// used as its hash.
+fn has_random_bits(uuid: &Uuid) -> bool {
+    let variant = (uuid.as_bytes()[8] & 0b11000000) >> 6;
+    matches!(uuid.get_version_num(), 4 | 7) && variant == 2
+}
+
+/// Mixes the bits of the given hash, so that hashes which only differ by a few bits
+/// end up very different.
+///
+/// This is the finalizer of SplitMix64.
+pub(crate) fn avalanche(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^ (hash >> 31)
+}
+
 macro_rules! not_supported {
     ($($method:ident : $ty:ty),+ $(,)?) => {$(
         fn $method(&mut self, _: $ty) {
@@ -43,71 +279,86 @@ macro_rules! not_supported {
 
 impl Hasher for UuidHasher {
     fn finish(&self) -> u64 {
+        assert!(self.written, "expected at least one UUID to be written");
+        assert_eq!(
+            self.pending, 0,
+            "expected the UUIDs of a slice to be written"
+        );
+
         self.hash
     }
 
     fn write(&mut self, bytes: &[u8]) {
-        assert_eq!(bytes.len(), 16);
-
-        let version = (bytes[6] & 0b11110000) >> 4;
+        let uuid = Uuid::from_slice(bytes).expect("expected the 16 bytes of a UUID");
+        let version = uuid.version();
 
         #[cfg(feature = "gxhash-3")]
-        if version != 3 && version != 7 {
-            self.hash = gxhash::gxhash64(bytes, 0);
+        if version != 4 && version != 6 && version != 7 {
+            self.combine(gxhash::gxhash64(bytes, 0));
             return;
         }
 
         #[cfg(not(feature = "gxhash-3"))]
-        assert!(version == 4 || version == 7);
+        assert!(version == 4 || version == 6 || version == 7);
 
         let variant = (bytes[8] & 0b11000000) >> 6;
         assert_eq!(variant, 2);
 
-        // UUIDv4s have the following bit pattern:
-        // ```
-        //  0                   1                   2                   3
-        //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |                           random_a                            |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |          random_a             |  ver  |       random_b        |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |var|                       random_c                            |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |                           random_c                            |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // ```
-        //
-        // UUIDv7s have the following bit pattern:
-        // ```
-        //  0                   1                   2                   3
-        //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |                           unix_ts_ms                          |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |          unix_ts_ms           |  ver  |       rand_a          |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |var|                        rand_b                             |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // |                            rand_b                             |
-        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
-        // ```
-        //
-        // We thus use the 8th byte (part of `random_b` and `rand_a` respectively) and the
-        // 7 last bytes (part of `random_c` and `rand_b` respectively) as the hash.
-
-        let mut out = [0; 8];
-        out[0] = bytes[7];
-        out[1] = bytes[9];
-        out[2] = bytes[10];
-        out[3] = bytes[11];
-        out[4] = bytes[12];
-        out[5] = bytes[13];
-        out[6] = bytes[14];
-        out[7] = bytes[15];
-
-        let hash = u64::from_be_bytes(out);
-        self.hash = hash;
+        let hash = u64::from_be_bytes(uuid.hash_bytes());
+        self.combine(hash);
+    }
+
+    /// Records the length of slices of UUIDs (e.g. of `[Uuid; 3]`), which is written
+    /// before their UUIDs, without mixing it into the hash.
+    ///
+    /// [`Hasher::finish()`] panics if fewer UUIDs than announced were written, so that
+    /// other `usize`s (e.g. the one of `(Uuid, usize)`) aren't silently ignored.
+    #[inline]
+    fn write_usize(&mut self, len: usize) {
+        self.pending += len;
+    }
+
+    not_supported!(
+        write_i8: i8,
+        write_i16: i16,
+        write_i32: i32,
+        write_i64: i64,
+        write_i128: i128,
+        write_isize: isize,
+        write_u8: u8,
+        write_u16: u16,
+        write_u32: u32,
+        write_u64: u64,
+        write_u128: u128,
+    );
+}
+
+impl Hasher for FallbackUuidHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+}
+
+impl Hasher for FoldingUuidHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let uuid = Uuid::from_slice(bytes).expect("expected the 16 bytes of a UUID");
+
+        // Clears the version (bits 48 to 51) and the variant (bits 64 and 65).
+        let bits = uuid.as_u128() & !(0xf << 76) & !(0b11 << 62);
+
+        let folded = (bits >> 64) as u64 ^ (bits as u64).rotate_left(32);
+        let hash = folded.wrapping_mul(0x9e3779b97f4a7c15);
+        self.hash = hash ^ (hash >> 32);
     }
 
     not_supported!(
@@ -125,3 +376,31 @@ impl Hasher for UuidHasher {
         write_usize: usize,
     );
 }
+
+impl<H: Hasher> Hasher for UuidOrHashHasher<H> {
+    fn finish(&self) -> u64 {
+        match self.state {
+            UuidOrHashState::Random(hash) => hash,
+            UuidOrHashState::Empty | UuidOrHashState::Fallback => self.fallback.finish(),
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let uuid = Uuid::from_slice(bytes).ok().filter(has_random_bits);
+
+        self.state = match (&self.state, uuid) {
+            (UuidOrHashState::Empty, Some(uuid)) => {
+                UuidOrHashState::Random(u64::from_be_bytes(uuid.hash_bytes()))
+            }
+            (UuidOrHashState::Random(hash), _) => {
+                self.fallback.write_u64(*hash);
+                self.fallback.write(bytes);
+                UuidOrHashState::Fallback
+            }
+            (UuidOrHashState::Empty | UuidOrHashState::Fallback, _) => {
+                self.fallback.write(bytes);
+                UuidOrHashState::Fallback
+            }
+        };
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::Hash,
+    ops::Deref,
+    sync::Arc,
+};
+
+use uuid::Uuid;
+
+use crate::UuidMap;
+
+/// A [`UuidMap`] which stores each distinct value once, and shares it between all
+/// the UUIDs it is inserted for.
+///
+/// This reduces the memory used by maps whose values are large and often repeated.
+///
+/// See [`InternedUuidMap::insert()`].
+pub struct InternedUuidMap<V> {
+    map: UuidMap<Arc<V>>,
+    // The number of UUIDs each value is shared with.
+    values: HashMap<Arc<V>, usize>,
+}
+
+impl<V: Hash + Eq> InternedUuidMap<V> {
+    /// Creates an empty [`InternedUuidMap`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            map: UuidMap::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of distinct values stored in the map.
+    #[inline]
+    pub fn distinct_values(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Inserts a value for the given UUID, sharing it with the other UUIDs which
+    /// already have an equal value.
+    ///
+    /// Returns the previous value for the UUID, if there was one.
+    pub fn insert(&mut self, uuid: Uuid, value: V) -> Option<Arc<V>> {
+        let value = match self.values.get_key_value(&value) {
+            Some((interned, _)) => interned.clone(),
+            None => Arc::new(value),
+        };
+        *self.values.entry(value.clone()).or_default() += 1;
+
+        let previous = self.map.insert(uuid, value)?;
+        self.release(&previous);
+        Some(previous)
+    }
+
+    /// Removes the value for the given UUID, and returns it if there was one.
+    ///
+    /// The value stops being interned once no UUID has it anymore.
+    pub fn remove(&mut self, uuid: &Uuid) -> Option<Arc<V>> {
+        let value = self.map.remove(uuid)?;
+        self.release(&value);
+        Some(value)
+    }
+
+    /// Decrements the number of UUIDs the given value is shared with, and stops
+    /// interning it if there are none left.
+    fn release(&mut self, value: &Arc<V>) {
+        let Some(count) = self.values.get_mut(value) else {
+            return;
+        };
+
+        *count -= 1;
+        if *count == 0 {
+            self.values.remove(value);
+        }
+    }
+}
+
+impl<V: Hash + Eq> Default for InternedUuidMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Deref for InternedUuidMap<V> {
+    type Target = UuidMap<Arc<V>>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<V: Debug> Debug for InternedUuidMap<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}
@@ -0,0 +1,156 @@
+use std::hash::Hasher;
+
+use uuid::Uuid;
+use uuid_like::UuidLike;
+
+/// A key which can be hashed by [`UuidHasher`][1].
+///
+/// This is implemented for [`Uuid`], which is what [`UuidHasher`][1] uses internally
+/// to validate and hash the bytes it is given. Keys implementing [`UuidLike`][2]
+/// are hashed as the [`Uuid`] they wrap and thus go through the same
+/// implementation.
+///
+/// Custom key types can implement this trait and forward their [`Hash`][3]
+/// implementation to [`UuidKey::hash_into()`], so that they can be used with
+/// [`UuidBuildHasher`][4].
+///
+/// [1]: crate::UuidHasher
+/// [2]: uuid_like::UuidLike
+/// [3]: std::hash::Hash
+/// [4]: crate::UuidBuildHasher
+pub trait UuidKey {
+    /// Returns the version of the key, as encoded in a UUID (e.g. `4` for UUIDv4s).
+    fn version(&self) -> u8;
+
+    /// Returns the random bytes of the key, which are used as its hash.
+    fn hash_bytes(&self) -> [u8; 8];
+
+    /// Feeds the key into the given [`Hasher`].
+    ///
+    /// This writes the key using the layout of a UUID, so that [`UuidHasher`][1]
+    /// can validate its version and use [`UuidKey::hash_bytes()`] as its hash.
+    ///
+    /// [1]: crate::UuidHasher
+    fn hash_into<H: Hasher>(&self, state: &mut H) {
+        let hash = self.hash_bytes();
+
+        let mut bytes = [0; 16];
+        bytes[6] = self.version() << 4;
+        bytes[7] = hash[0];
+        bytes[8] = 0b10000000;
+        bytes[9..].copy_from_slice(&hash[1..]);
+
+        state.write(&bytes);
+    }
+}
+
+impl UuidKey for Uuid {
+    #[inline]
+    fn version(&self) -> u8 {
+        (self.as_bytes()[6] & 0b11110000) >> 4
+    }
+
+    fn hash_bytes(&self) -> [u8; 8] {
+        let bytes = self.as_bytes();
+
+        // UUIDv4s have the following bit pattern:
+        // ```
+        //  0                   1                   2                   3
+        //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |                           random_a                            |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |          random_a             |  ver  |       random_b        |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |var|                       random_c                            |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |                           random_c                            |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // ```
+        //
+        // UUIDv7s have the following bit pattern:
+        // ```
+        //  0                   1                   2                   3
+        //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |                           unix_ts_ms                          |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |          unix_ts_ms           |  ver  |       rand_a          |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |var|                        rand_b                             |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |                            rand_b                             |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // ```
+        //
+        // We thus use the 8th byte (part of `random_b` and `rand_a` respectively) and the
+        // 7 last bytes (part of `random_c` and `rand_b` respectively) as the hash.
+        //
+        // UUIDv6s have the following bit pattern:
+        // ```
+        //  0                   1                   2                   3
+        //  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |                           time_high                           |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |           time_mid            |  ver  |       time_low        |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |var|         clock_seq         |             node              |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // |                              node                             |
+        // +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+        // ```
+        //
+        // We use `clock_seq` and `node` (the 62 last bits) as the hash, which differ
+        // between generators. Since they are usually the same for all the UUIDs of a
+        // single generator, we also XOR `time_low` (the 12 bits which change the most
+        // often) into the 12 last bits, so that those UUIDs don't all collide.
+        if self.version() == 6 {
+            let mut out = [0; 8];
+            out[0] = bytes[8] & 0b00111111;
+            out[1..].copy_from_slice(&bytes[9..]);
+            out[6] ^= bytes[6] & 0b00001111;
+            out[7] ^= bytes[7];
+
+            return out;
+        }
+
+        let mut out = [0; 8];
+        out[0] = bytes[7];
+        out[1] = bytes[9];
+        out[2] = bytes[10];
+        out[3] = bytes[11];
+        out[4] = bytes[12];
+        out[5] = bytes[13];
+        out[6] = bytes[14];
+        out[7] = bytes[15];
+
+        out
+    }
+}
+
+/// An adapter implementing [`UuidKey`] for any [`UuidLike`] type.
+///
+/// [`UuidKey`] can't be implemented for every [`UuidLike`] type directly, as such a
+/// blanket implementation would conflict with the one for [`Uuid`]. Wrapping a
+/// [`UuidLike`] value in this type instead exposes its version and hash bytes the
+/// same way as the [`Uuid`] it represents.
+///
+/// This hashes as the wrapped value, so it can be used as a key with
+/// [`UuidBuildHasher`][1] like the wrapped type itself.
+///
+/// [1]: crate::UuidBuildHasher
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UuidLikeKey<K>(pub K);
+
+impl<K: UuidLike> UuidKey for UuidLikeKey<K> {
+    #[inline]
+    fn version(&self) -> u8 {
+        self.0.get_version_num() as u8
+    }
+
+    #[inline]
+    fn hash_bytes(&self) -> [u8; 8] {
+        Uuid::from_bytes(self.0.into_bytes()).hash_bytes()
+    }
+}
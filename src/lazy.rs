@@ -0,0 +1,94 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use uuid::Uuid;
+
+use crate::UuidMap;
+
+/// A [`UuidMap`] whose values are computed the first time they are accessed, and
+/// cached until they are evicted.
+///
+/// See [`LazyUuidMap::get_or_compute()`] and [`LazyUuidMap::evict()`].
+pub struct LazyUuidMap<V> {
+    map: UuidMap<V>,
+    on_evict: Option<Box<dyn FnMut(Uuid, V) + Send>>,
+}
+
+impl<V> LazyUuidMap<V> {
+    /// Creates an empty [`LazyUuidMap`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            map: UuidMap::new(),
+            on_evict: None,
+        }
+    }
+
+    /// Creates an empty [`LazyUuidMap`] which calls `on_evict` with the entries
+    /// removed by [`LazyUuidMap::evict()`] and [`LazyUuidMap::clear()`].
+    #[inline]
+    pub fn with_eviction_hook(on_evict: impl FnMut(Uuid, V) + Send + 'static) -> Self {
+        Self {
+            map: UuidMap::new(),
+            on_evict: Some(Box::new(on_evict)),
+        }
+    }
+
+    /// Returns the value for the given UUID, computing it using `f` and caching it if
+    /// it isn't already.
+    pub fn get_or_compute(&mut self, key: Uuid, f: impl FnOnce() -> V) -> &V {
+        self.map.entry(key).or_insert_with(f)
+    }
+
+    /// Removes the cached value for the given UUID, passing it to the eviction hook
+    /// if there is one.
+    ///
+    /// Returns whether a value was cached.
+    pub fn evict(&mut self, key: &Uuid) -> bool {
+        let Some((key, value)) = self.map.remove_entry(key) else {
+            return false;
+        };
+
+        if let Some(on_evict) = &mut self.on_evict {
+            on_evict(key, value);
+        }
+
+        true
+    }
+
+    /// Removes all the cached values, passing them to the eviction hook if there is
+    /// one.
+    pub fn clear(&mut self) {
+        match &mut self.on_evict {
+            Some(on_evict) => self
+                .map
+                .drain()
+                .for_each(|(key, value)| on_evict(key, value)),
+            None => self.map.clear(),
+        }
+    }
+}
+
+impl<V> Default for LazyUuidMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Deref for LazyUuidMap<V> {
+    type Target = UuidMap<V>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<V: Debug> Debug for LazyUuidMap<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}
@@ -1,22 +1,64 @@
 use std::{
-    collections::{HashMap, HashSet, hash_map, hash_set},
+    collections::{BTreeMap, HashMap, HashSet, hash_map, hash_set},
     fmt::{self, Debug, Formatter},
-    ops::{Deref, DerefMut},
+    hash::{BuildHasher, DefaultHasher, Hash, Hasher},
+    mem,
+    num::{NonZeroU64, NonZeroUsize},
+    ops::{BitAnd, BitOr, BitXor, Deref, DerefMut, Sub},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use indexmap::{IndexMap, IndexSet, map, set};
 use uuid::Uuid;
 
 pub use self::{
-    hasher::{UuidBuildHasher, UuidHasher},
+    bimap::UuidBiMap,
+    block::UuidBlock,
+    diff::MapDiff,
+    error::{CanonicalBytesError, PrefixCompressedError, UnsupportedVersionError},
+    estimator::UuidCardinalityEstimator,
+    hasher::{
+        FallbackUuidBuildHasher, FallbackUuidHasher, FoldingUuidBuildHasher, FoldingUuidHasher,
+        UuidBuildHasher, UuidHasher, UuidOrHashBuildHasher, UuidOrHashHasher,
+    },
+    intern::InternedUuidMap,
+    key::{UuidKey, UuidLikeKey},
+    lazy::LazyUuidMap,
     like::{UuidLikeIndexMap, UuidLikeIndexSet, UuidLikeMap, UuidLikeSet},
+    multi::UuidMultiMap,
+    rehashed::Rehashed,
+    stats::BuildStats,
+    window::V7WindowAggregator,
 };
 
+#[cfg(feature = "dashmap-6")]
+pub use self::ext::dashmap::UuidDashMap;
+#[cfg(feature = "rkyv-08")]
+pub use self::ext::rkyv::LoadFactor;
+#[cfg(feature = "serde-1")]
+pub use self::ext::serde::{
+    DenyDuplicateKeys, deserialize_all_v7, deserialize_from_seq, serialize_as_seq,
+};
+#[cfg(feature = "speedy-08")]
+pub use self::ext::speedy::LoggedUuidMap;
+
+mod bimap;
+mod block;
+mod diff;
+mod error;
+mod estimator;
 mod ext;
 mod hasher;
+mod intern;
+mod key;
+mod lazy;
 mod like;
+mod multi;
+mod rehashed;
+mod stats;
 #[cfg(test)]
 mod tests;
+mod window;
 
 /// A wrapper around an [`HashMap`] where the keys are UUIDv4s or UUIDv7s and don't
 /// require hashing.
@@ -27,10 +69,14 @@ mod tests;
 /// If the `gxhash-3` feature is enabled, then [`gxhash`] is used as a fallback to
 /// allow this hasher to be used with other UUID versions.
 ///
+/// If the `serde-1` feature is enabled, this is serialized as a map. See
+/// `serialize_as_seq()` to serialize it as a sequence of `(key, value)` pairs
+/// instead.
+///
 /// ## Panics
 ///
 /// This will panic if trying to use other UUID versions.
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub struct UuidMap<V>(HashMap<Uuid, V, UuidBuildHasher>);
 
@@ -43,10 +89,14 @@ pub struct UuidMap<V>(HashMap<Uuid, V, UuidBuildHasher>);
 /// If the `gxhash-3` feature is enabled, then [`gxhash`] is used as a fallback to
 /// allow this hasher to be used with other UUID versions.
 ///
+/// If the `serde-1` feature is enabled, this is serialized as a map, in order. See
+/// `serialize_as_seq()` to serialize it as a sequence of `(key, value)` pairs
+/// instead.
+///
 /// ## Panics
 ///
 /// This will panic if trying to use other UUID versions.
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub struct UuidIndexMap<V>(IndexMap<Uuid, V, UuidBuildHasher>);
 
@@ -59,10 +109,12 @@ pub struct UuidIndexMap<V>(IndexMap<Uuid, V, UuidBuildHasher>);
 /// If the `gxhash-3` feature is enabled, then [`gxhash`] is used as a fallback to
 /// allow this hasher to be used with other UUID versions.
 ///
+/// If the `serde-1` feature is enabled, this is serialized as a sequence.
+///
 /// ## Panics
 ///
 /// This will panic if trying to use other UUID versions.
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct UuidSet(HashSet<Uuid, UuidBuildHasher>);
 
@@ -75,13 +127,43 @@ pub struct UuidSet(HashSet<Uuid, UuidBuildHasher>);
 /// If the `gxhash-3` feature is enabled, then [`gxhash`] is used as a fallback to
 /// allow this hasher to be used with other UUID versions.
 ///
+/// If the `serde-1` feature is enabled, this is serialized as a sequence, in order.
+///
 /// ## Panics
 ///
 /// This will panic if trying to use other UUID versions.
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Default, Clone, PartialEq, Eq)]
 pub struct UuidIndexSet(IndexSet<Uuid, UuidBuildHasher>);
 
+/// A [`HashMap`] whose keys are UUIDs, hashed using all their random bits.
+///
+/// This uses [`FoldingUuidHasher`] as the hasher, which is slightly slower than
+/// [`UuidHasher`] but reduces collisions between UUIDs which only differ by the bits
+/// [`UuidHasher`] doesn't use.
+pub type FoldingUuidMap<V> = HashMap<Uuid, V, FoldingUuidBuildHasher>;
+
+/// A [`HashSet`] whose keys are UUIDs, hashed using all their random bits.
+///
+/// This uses [`FoldingUuidHasher`] as the hasher, which is slightly slower than
+/// [`UuidHasher`] but reduces collisions between UUIDs which only differ by the bits
+/// [`UuidHasher`] doesn't use.
+pub type FoldingUuidSet = HashSet<Uuid, FoldingUuidBuildHasher>;
+
+/// A [`HashMap`] whose keys are UUIDs of any version.
+///
+/// This uses [`UuidOrHashHasher`] as the hasher, so that the random bits of UUIDv4s
+/// and UUIDv7s are used instead of hashing them, while other UUIDs are hashed using
+/// `H` instead of panicking.
+pub type UuidOrHashMap<V, H = DefaultHasher> = HashMap<Uuid, V, UuidOrHashBuildHasher<H>>;
+
+/// A [`HashSet`] whose keys are UUIDs of any version.
+///
+/// This uses [`UuidOrHashHasher`] as the hasher, so that the random bits of UUIDv4s
+/// and UUIDv7s are used instead of hashing them, while other UUIDs are hashed using
+/// `H` instead of panicking.
+pub type UuidOrHashSet<H = DefaultHasher> = HashSet<Uuid, UuidOrHashBuildHasher<H>>;
+
 impl<V> UuidMap<V> {
     /// Creates an empty [`UuidMap`].
     ///
@@ -99,6 +181,636 @@ impl<V> UuidMap<V> {
         Self(HashMap::with_capacity_and_hasher(capacity, UuidBuildHasher))
     }
 
+    /// Converts the [`UuidMap`] into the [`HashMap`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> HashMap<Uuid, V, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`HashMap`] wrapped by the [`UuidMap`].
+    #[inline]
+    pub fn as_inner(&self) -> &HashMap<Uuid, V, UuidBuildHasher> {
+        &self.0
+    }
+
+    /// Returns a [`UuidSet`] containing the UUIDs of the map.
+    pub fn key_set(&self) -> UuidSet {
+        let mut set = UuidSet::with_capacity(self.len());
+        set.extend(self.keys().copied());
+        set
+    }
+
+    /// Creates a [`UuidMap`] from index-aligned vectors of UUIDs and values.
+    ///
+    /// This is the inverse of [`UuidMap::into_parts()`].
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if `keys` and `values` don't have the same length.
+    pub fn from_columns(keys: Vec<Uuid>, values: Vec<V>) -> Self {
+        assert_eq!(keys.len(), values.len());
+
+        let mut map = Self::with_capacity(keys.len());
+        map.extend(keys.into_iter().zip(values));
+        map
+    }
+
+    /// Creates a [`UuidMap`] from an iterator, like [`FromIterator::from_iter()`],
+    /// while recording [`BuildStats`] about it.
+    pub fn from_iter_with_stats<T>(iter: T) -> (Self, BuildStats)
+    where
+        T: IntoIterator<Item = (Uuid, V)>,
+    {
+        let iter = iter.into_iter();
+
+        let mut map = Self::with_capacity(iter.size_hint().0);
+        let mut stats = BuildStats::default();
+
+        for (uuid, value) in iter {
+            stats.inserted += 1;
+            if map.insert(uuid, value).is_some() {
+                stats.overwritten += 1;
+            }
+        }
+
+        stats.capacity = map.capacity();
+        (map, stats)
+    }
+
+    /// Splits the map into index-aligned vectors of UUIDs and values, in arbitrary
+    /// order.
+    ///
+    /// This is the inverse of [`UuidMap::from_columns()`].
+    pub fn into_parts(self) -> (Vec<Uuid>, Vec<V>) {
+        let mut keys = Vec::with_capacity(self.len());
+        let mut values = Vec::with_capacity(self.len());
+
+        for (key, value) in self.0 {
+            keys.push(key);
+            values.push(value);
+        }
+
+        (keys, values)
+    }
+
+    /// Converts the map into a [`BTreeMap`], ordered by UUID.
+    pub fn into_btree_map(self) -> BTreeMap<Uuid, V> {
+        self.0.into_iter().collect()
+    }
+
+    /// Converts the map into a [`UuidIndexMap`] whose entries are sorted by value in
+    /// ascending order, with ties broken by UUID.
+    pub fn into_index_map_sorted_by_value(self) -> UuidIndexMap<V>
+    where
+        V: Ord,
+    {
+        let mut entries: Vec<_> = self.0.into_iter().collect();
+        entries.sort_unstable_by(|(a, av), (b, bv)| av.cmp(bv).then_with(|| a.cmp(b)));
+
+        entries.into_iter().collect()
+    }
+
+    /// Clones the entries of the map into a [`BTreeMap`], ordered by UUID.
+    pub fn to_btree_map(&self) -> BTreeMap<Uuid, V>
+    where
+        V: Clone,
+    {
+        self.iter()
+            .map(|(uuid, value)| (*uuid, value.clone()))
+            .collect()
+    }
+
+    /// Reserves capacity for at least `estimate * (1 + margin)` entries in total.
+    ///
+    /// This is meant to be used with an estimate computed by a
+    /// [`UuidCardinalityEstimator`], with `margin` accounting for its error.
+    pub fn reserve_for_estimate(&mut self, estimate: u64, margin: f64) {
+        let target = (estimate as f64 * (1.0 + margin)).ceil() as usize;
+        self.0.reserve(target.saturating_sub(self.0.len()));
+    }
+
+    /// Returns whether at least two UUIDs of the map have the same random bits, and
+    /// thus the same hash.
+    pub fn has_entropy_collisions(&self) -> bool {
+        let mut hashes = HashSet::with_capacity(self.len());
+        !self
+            .keys()
+            .all(|uuid| hashes.insert(UuidBuildHasher.hash_one(uuid)))
+    }
+
+    /// Returns whether more than `threshold` UUIDs of the map have the same hash.
+    ///
+    /// This can be used to detect UUIDs crafted to collide (e.g. by an attacker), and
+    /// switch to [`FallbackUuidHasher`] using
+    /// [`UuidMap::rehash_colliding_with_fallback()`].
+    pub fn detect_hash_flood(&self, threshold: usize) -> bool {
+        let mut counts = HashMap::<u64, usize>::with_capacity(self.len());
+        self.keys().any(|uuid| {
+            let count = counts.entry(UuidBuildHasher.hash_one(uuid)).or_default();
+            *count += 1;
+            *count > threshold
+        })
+    }
+
+    /// Returns the UUIDs sharing the most common hash, or an empty vector if the map
+    /// is empty.
+    ///
+    /// If several hashes are shared by the same number of UUIDs, one of them is
+    /// chosen arbitrarily.
+    pub fn densest_bucket(&self) -> Vec<&Uuid> {
+        let mut buckets = HashMap::<u64, Vec<&Uuid>>::with_capacity(self.len());
+        for uuid in self.keys() {
+            buckets
+                .entry(UuidBuildHasher.hash_one(uuid))
+                .or_default()
+                .push(uuid);
+        }
+
+        buckets
+            .into_values()
+            .max_by_key(Vec::len)
+            .unwrap_or_default()
+    }
+
+    /// Returns the map unchanged if it doesn't have entropy collisions
+    /// ([`Rehashed::Unchanged`]), or its entries rehashed using [`FallbackUuidHasher`]
+    /// otherwise ([`Rehashed::Fallback`]).
+    ///
+    /// See [`UuidMap::has_entropy_collisions()`].
+    pub fn rehash_colliding_with_fallback(self) -> Rehashed<V> {
+        if !self.has_entropy_collisions() {
+            return Rehashed::Unchanged(self);
+        }
+
+        let mut map = HashMap::with_capacity_and_hasher(self.len(), FallbackUuidBuildHasher);
+        map.extend(self.0);
+        Rehashed::Fallback(map)
+    }
+
+    /// Splits the map in two, depending on whether its UUIDs match the given predicate.
+    ///
+    /// The first map contains the entries for which `pred` returned `true`, and the
+    /// second one the others.
+    pub fn partition_keys(self, pred: impl Fn(&Uuid) -> bool) -> (UuidMap<V>, UuidMap<V>) {
+        let mut matching = UuidMap::new();
+        let mut others = UuidMap::new();
+
+        for (uuid, value) in self.0 {
+            if pred(&uuid) {
+                matching.insert(uuid, value);
+            } else {
+                others.insert(uuid, value);
+            }
+        }
+
+        (matching, others)
+    }
+
+    /// Returns the distinct values of the map along with the number of UUIDs they are
+    /// associated with, sorted from the most to the least frequent.
+    ///
+    /// Values with the same frequency are returned in arbitrary order.
+    pub fn values_by_frequency(&self) -> Vec<(&V, usize)>
+    where
+        V: Hash + Eq,
+    {
+        let mut counts = HashMap::<_, usize>::new();
+        for value in self.values() {
+            *counts.entry(value).or_default() += 1;
+        }
+
+        let mut frequencies: Vec<_> = counts.into_iter().collect();
+        frequencies.sort_unstable_by(|(_, lhs), (_, rhs)| rhs.cmp(lhs));
+        frequencies
+    }
+
+    /// Returns a new map containing only the entries whose UUID is in `allow`.
+    pub fn project(&self, allow: &UuidSet) -> UuidMap<V>
+    where
+        V: Clone,
+    {
+        allow
+            .iter()
+            .filter_map(|uuid| self.get_key_value(uuid))
+            .map(|(uuid, value)| (*uuid, value.clone()))
+            .collect()
+    }
+
+    /// Returns the UUIDs of this map which aren't in `other`, regardless of their
+    /// values.
+    pub fn key_difference<W>(&self, other: &UuidMap<W>) -> UuidSet {
+        self.keys()
+            .filter(|uuid| !other.contains_key(uuid))
+            .copied()
+            .collect()
+    }
+
+    /// Returns whether this map and `other` contain the same UUIDs, regardless of
+    /// their values.
+    pub fn same_keys<W>(&self, other: &UuidMap<W>) -> bool {
+        // Maps of different lengths can't have the same keys, and maps of the same
+        // length only need to be checked in one direction.
+        self.len() == other.len() && self.keys().all(|uuid| other.contains_key(uuid))
+    }
+
+    /// Returns the number of UUIDs of each version in the map, indexed by version.
+    pub fn version_histogram(&self) -> [usize; 16] {
+        let mut histogram = [0; 16];
+        for uuid in self.keys() {
+            histogram[uuid.get_version_num()] += 1;
+        }
+
+        histogram
+    }
+
+    /// Splits the map into several maps, depending on the discriminant returned by
+    /// `key_fn` for each value.
+    pub fn demux<K: Hash + Eq>(self, key_fn: impl Fn(&V) -> K) -> HashMap<K, UuidMap<V>> {
+        let mut maps = HashMap::<_, UuidMap<V>>::new();
+        for (uuid, value) in self.0 {
+            maps.entry(key_fn(&value)).or_default().insert(uuid, value);
+        }
+
+        maps
+    }
+
+    /// Returns the differences between this map (the old one) and `other` (the new
+    /// one).
+    pub fn diff(&self, other: &UuidMap<V>) -> MapDiff<V>
+    where
+        V: Clone + PartialEq,
+    {
+        let mut removed = UuidMap::new();
+        let mut changed = UuidMap::new();
+
+        for (uuid, old) in self.iter() {
+            match other.get(uuid) {
+                Some(new) if new != old => {
+                    changed.insert(*uuid, (old.clone(), new.clone()));
+                }
+                Some(_) => {}
+                None => {
+                    removed.insert(*uuid, old.clone());
+                }
+            }
+        }
+
+        let added = other
+            .iter()
+            .filter(|(uuid, _)| !self.contains_key(uuid))
+            .map(|(uuid, new)| (*uuid, new.clone()))
+            .collect();
+
+        MapDiff {
+            added,
+            removed,
+            changed,
+        }
+    }
+
+    /// Applies the given differences to the map, inserting the entries which were
+    /// added or changed and removing the ones which were removed.
+    ///
+    /// Applying the differences between this map and another one (see
+    /// [`UuidMap::diff()`]) makes this map equal to the other one.
+    pub fn apply_diff(&mut self, diff: &MapDiff<V>)
+    where
+        V: Clone,
+    {
+        for uuid in diff.removed.keys() {
+            self.0.remove(uuid);
+        }
+
+        self.0.extend(
+            diff.added
+                .iter()
+                .chain(diff.changed.iter().map(|(uuid, (_, new))| (uuid, new)))
+                .map(|(uuid, value)| (*uuid, value.clone())),
+        );
+    }
+
+    /// Groups the UUIDs of the map by their value.
+    pub fn invert(&self) -> HashMap<V, UuidSet>
+    where
+        V: Hash + Eq + Clone,
+    {
+        let mut inverted = HashMap::<_, UuidSet>::new();
+        for (uuid, value) in self.iter() {
+            match inverted.get_mut(value) {
+                Some(uuids) => uuids.insert(*uuid),
+                None => inverted.entry(value.clone()).or_default().insert(*uuid),
+            };
+        }
+
+        inverted
+    }
+
+    /// Returns the UUIDs whose value satisfies `pred`.
+    pub fn keys_where(&self, pred: impl Fn(&V) -> bool) -> UuidSet {
+        self.iter()
+            .filter(|(_, value)| pred(value))
+            .map(|(uuid, _)| *uuid)
+            .collect()
+    }
+
+    /// Returns the UUIDs whose value isn't equal to the value of any other UUID.
+    ///
+    /// This is useful to find the duplicates of maps which are expected to be
+    /// injective: the UUIDs which aren't returned share their value.
+    pub fn unique_valued_keys(&self) -> UuidSet
+    where
+        V: Hash + Eq,
+    {
+        let mut counts = HashMap::<_, usize>::with_capacity(self.len());
+        for value in self.values() {
+            *counts.entry(value).or_default() += 1;
+        }
+
+        self.iter()
+            .filter(|(_, value)| counts[value] == 1)
+            .map(|(uuid, _)| *uuid)
+            .collect()
+    }
+
+    /// Groups the UUIDs of the map by the virtual node they belong to on a
+    /// consistent-hashing ring with `ring_size` positions.
+    ///
+    /// `nodes` contains the position of each virtual node, and the returned vector
+    /// contains one set of UUIDs per virtual node, in the same order. Each UUID
+    /// belongs to the first virtual node at or after its position, wrapping around
+    /// the ring. See [`UuidBuildHasher::ring_position()`].
+    pub fn keys_by_ring_node(&self, nodes: &[u64], ring_size: NonZeroU64) -> Vec<UuidSet> {
+        let mut groups: Vec<_> = nodes.iter().map(|_| UuidSet::new()).collect();
+        if nodes.is_empty() {
+            return groups;
+        }
+
+        let mut order: Vec<_> = (0..nodes.len()).collect();
+        order.sort_unstable_by_key(|idx| nodes[*idx]);
+
+        for uuid in self.keys() {
+            let position = UuidBuildHasher.ring_position(uuid, ring_size);
+            let idx = order.partition_point(|idx| nodes[*idx] < position);
+            groups[order[idx % order.len()]].insert(*uuid);
+        }
+
+        groups
+    }
+
+    /// Consumes the map, grouping its entries by the shard their UUID belongs to.
+    ///
+    /// The returned vector contains one vector of entries per shard. See
+    /// [`UuidBuildHasher::shard_of()`].
+    pub fn into_grouped_by_shard(self, shards: NonZeroUsize) -> Vec<Vec<(Uuid, V)>> {
+        let mut groups: Vec<_> = (0..shards.get()).map(|_| Vec::new()).collect();
+        for (uuid, value) in self.0 {
+            groups[UuidBuildHasher.shard_of(&uuid, shards)].push((uuid, value));
+        }
+
+        groups
+    }
+
+    /// Splits the map into maps of at most `chunk_size` entries, taking its entries in
+    /// arbitrary order.
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if `chunk_size` is `0`.
+    pub fn into_chunks(self, chunk_size: usize) -> Vec<UuidMap<V>> {
+        into_chunks(self.0.into_iter(), chunk_size)
+    }
+
+    /// Removes the entries whose UUID isn't in `keep`, in place.
+    ///
+    /// This is the in-place counterpart of [`UuidMap::project()`].
+    pub fn retain_in_set(&mut self, keep: &UuidSet) {
+        self.0.retain(|uuid, _| keep.contains(uuid));
+    }
+
+    /// Only keeps the entries of the `n` most recent UUIDv7s of the map, removing the
+    /// entries of the older ones.
+    ///
+    /// Entries whose UUID is of another version are kept. UUIDv7s with the same
+    /// timestamp are ordered by their random bits.
+    pub fn retain_newest_n(&mut self, n: usize) {
+        let mut v7s: Vec<_> = self
+            .keys()
+            .filter(|uuid| uuid.get_version_num() == 7)
+            .copied()
+            .collect();
+
+        if v7s.len() <= n {
+            return;
+        }
+
+        // UUIDv7s are ordered by their timestamp first, so this moves the oldest
+        // ones before `older`, without sorting them.
+        let older = v7s.len() - n;
+        v7s.select_nth_unstable(older - 1);
+
+        for uuid in &v7s[..older] {
+            self.0.remove(uuid);
+        }
+    }
+
+    /// Removes the entries whose UUID is a UUIDv7 with an embedded timestamp after
+    /// `cutoff`, and returns them.
+    ///
+    /// Entries whose UUID is of another version are kept.
+    pub fn split_off_after(&mut self, cutoff: SystemTime) -> UuidMap<V> {
+        self.0
+            .extract_if(|uuid, _| v7_time(uuid).is_some_and(|time| time > cutoff))
+            .collect()
+    }
+
+    /// Returns the number of UUIDv7s of the map whose embedded timestamp is after
+    /// `cutoff`, without modifying it.
+    ///
+    /// UUIDs of other versions aren't counted, as they don't embed a timestamp. This
+    /// is the number of entries [`UuidMap::split_off_after()`] would remove.
+    pub fn count_newer_than(&self, cutoff: SystemTime) -> usize {
+        self.keys()
+            .filter(|uuid| v7_time(uuid).is_some_and(|time| time > cutoff))
+            .count()
+    }
+
+    /// Returns an iterator visiting the entries whose UUID is a UUIDv7 in
+    /// chronological order.
+    ///
+    /// UUIDs of other versions are skipped, as they don't embed a timestamp. UUIDv7s
+    /// with the same timestamp are ordered by their remaining bytes. This sorts the
+    /// entries before visiting them.
+    pub fn iter_chronological(&self) -> impl Iterator<Item = (&Uuid, &V)> {
+        let mut entries: Vec<_> = self
+            .iter()
+            .filter(|(uuid, _)| uuid.get_version_num() == 7)
+            .collect();
+
+        // The timestamp is stored in the first 48 bits, so sorting by UUID sorts by
+        // timestamp first.
+        entries.sort_unstable_by_key(|(uuid, _)| *uuid);
+        entries.into_iter()
+    }
+
+    /// Returns an iterator visiting the entries whose UUID is a UUIDv7 with an
+    /// embedded timestamp in `start..end`, in arbitrary order.
+    ///
+    /// UUIDs of other versions are skipped, as they don't embed a timestamp.
+    pub fn range_by_time(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> impl Iterator<Item = (&Uuid, &V)> {
+        self.iter()
+            .filter(move |(uuid, _)| v7_time(uuid).is_some_and(|time| (start..end).contains(&time)))
+    }
+
+    /// Returns the UUIDv7 of the map whose embedded timestamp is the closest to
+    /// `target`, or `None` if the map doesn't contain any UUIDv7.
+    ///
+    /// UUIDs of other versions are ignored. If several UUIDs are as close to
+    /// `target`, one of them is chosen arbitrarily.
+    pub fn nearest_by_time(&self, target: SystemTime) -> Option<&Uuid> {
+        self.keys()
+            .filter_map(|uuid| {
+                let time = v7_time(uuid)?;
+                let distance = match time.duration_since(target) {
+                    Ok(distance) => distance,
+                    Err(err) => err.duration(),
+                };
+
+                Some((distance, uuid))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, uuid)| uuid)
+    }
+
+    /// Swaps the contents of this map with those of `other`, in `O(1)`.
+    ///
+    /// See [`mem::swap()`].
+    #[inline]
+    pub fn swap(&mut self, other: &mut UuidMap<V>) {
+        mem::swap(&mut self.0, &mut other.0);
+    }
+
+    /// Takes the entries of the map, leaving an empty map behind.
+    ///
+    /// The returned map keeps the allocation of this one, and thus has the same
+    /// capacity. This map is left without any allocation, with a capacity of `0`.
+    ///
+    /// See [`mem::take()`].
+    #[inline]
+    pub fn take(&mut self) -> UuidMap<V> {
+        UuidMap(mem::take(&mut self.0))
+    }
+
+    /// Inserts an entry, unless its UUID can't be hashed by [`UuidHasher`], and
+    /// returns a mutable reference to its value.
+    ///
+    /// Unlike [`HashMap::insert()`], this doesn't panic. If the map already contains
+    /// the UUID, its value is replaced.
+    pub fn try_insert(&mut self, uuid: Uuid, value: V) -> Result<&mut V, UnsupportedVersionError> {
+        if !UuidHasher::supports(&uuid) {
+            return Err(UnsupportedVersionError(uuid));
+        }
+
+        match self.0.entry(uuid) {
+            hash_map::Entry::Occupied(entry) => {
+                let previous = entry.into_mut();
+                *previous = value;
+                Ok(previous)
+            }
+            hash_map::Entry::Vacant(entry) => Ok(entry.insert(value)),
+        }
+    }
+
+    /// Extends the map with the given entries, stopping at the first one whose UUID
+    /// can't be hashed by [`UuidHasher`].
+    ///
+    /// Unlike [`Extend::extend()`], this doesn't panic. Entries preceding the
+    /// unsupported one are inserted, and the ones following it aren't consumed.
+    pub fn try_extend<T: IntoIterator<Item = (Uuid, V)>>(
+        &mut self,
+        iter: T,
+    ) -> Result<(), UnsupportedVersionError> {
+        for (uuid, value) in iter {
+            if !UuidHasher::supports(&uuid) {
+                return Err(UnsupportedVersionError(uuid));
+            }
+
+            self.0.insert(uuid, value);
+        }
+
+        Ok(())
+    }
+
+    /// Creates a [`UuidMap`] from the given UUIDs, computing the value of each one by
+    /// calling `f`.
+    ///
+    /// This fails if one of the UUIDs can't be hashed by [`UuidHasher`], in which
+    /// case `f` isn't called for the UUIDs following it.
+    pub fn from_keys<F>(
+        keys: impl IntoIterator<Item = Uuid>,
+        f: F,
+    ) -> Result<Self, UnsupportedVersionError>
+    where
+        F: Fn(&Uuid) -> V,
+    {
+        keys.into_iter()
+            .map(|uuid| match UuidHasher::supports(&uuid) {
+                true => Ok((uuid, f(&uuid))),
+                false => Err(UnsupportedVersionError(uuid)),
+            })
+            .collect()
+    }
+
+    /// Replaces all the entries of the map with the given ones, unless one of their
+    /// UUIDs can't be hashed by [`UuidHasher`].
+    ///
+    /// All the entries are validated before the map is modified, so it is left
+    /// untouched if this fails. Otherwise, the map is cleared and the entries are
+    /// inserted into it, reusing its allocation.
+    pub fn replace_all<T: IntoIterator<Item = (Uuid, V)>>(
+        &mut self,
+        iter: T,
+    ) -> Result<(), UnsupportedVersionError> {
+        let entries = iter
+            .into_iter()
+            .map(|(uuid, value)| match UuidHasher::supports(&uuid) {
+                true => Ok((uuid, value)),
+                false => Err(UnsupportedVersionError(uuid)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.0.clear();
+        self.0.extend(entries);
+
+        Ok(())
+    }
+
+    /// An iterator visiting all entries in the order of the buckets they are stored
+    /// in, which follows the memory layout of the map.
+    ///
+    /// This is the same order as [`HashMap::iter()`], but is explicitly documented to
+    /// be the physical order, making it suitable for prefetch-friendly scans. It
+    /// changes whenever the map is resized.
+    #[inline]
+    pub fn iter_bucket_order(&self) -> hash_map::Iter<'_, Uuid, V> {
+        self.0.iter()
+    }
+
+    /// An iterator visiting all entries in arbitrary order, along with the index of
+    /// the shard their UUID belongs to, out of `shards`.
+    ///
+    /// See [`UuidBuildHasher::shard_of()`].
+    pub fn iter_with_shard(
+        &self,
+        shards: NonZeroUsize,
+    ) -> impl Iterator<Item = (usize, &Uuid, &V)> {
+        self.iter()
+            .map(move |(uuid, value)| (UuidBuildHasher.shard_of(uuid, shards), uuid, value))
+    }
+
     /// Creates a consuming iterator visiting all UUIDs in arbitrary order.
     ///
     /// See [`HashMap::into_keys()`].
@@ -136,7 +848,26 @@ impl<V> UuidIndexMap<V> {
         ))
     }
 
-    /// Creates a consuming iterator visiting all UUIDs in arbitrary order.
+    /// Converts the [`UuidIndexMap`] into the [`IndexMap`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> IndexMap<Uuid, V, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`IndexMap`] wrapped by the [`UuidIndexMap`].
+    #[inline]
+    pub fn as_inner(&self) -> &IndexMap<Uuid, V, UuidBuildHasher> {
+        &self.0
+    }
+
+    /// Returns a [`UuidIndexSet`] containing the UUIDs of the map, in the same order.
+    pub fn key_set(&self) -> UuidIndexSet {
+        let mut set = UuidIndexSet::with_capacity(self.len());
+        set.extend(self.keys().copied());
+        set
+    }
+
+    /// Creates a consuming iterator visiting all UUIDs in order.
     ///
     /// See [`IndexMap::into_keys()`].
     #[inline]
@@ -144,13 +875,103 @@ impl<V> UuidIndexMap<V> {
         self.0.into_keys()
     }
 
-    /// Creates a consuming iterator visiting all values in arbitrary order.
+    /// Creates a consuming iterator visiting all values in order.
     ///
     /// See [`IndexMap::into_values()`].
     #[inline]
     pub fn into_values(self) -> map::IntoValues<Uuid, V> {
         self.0.into_values()
     }
+
+    /// Returns whether the UUIDv7s of the map are in chronological order, i.e. whether
+    /// each one has an embedded timestamp after or equal to the one of the previous
+    /// UUIDv7.
+    ///
+    /// UUIDs of other versions are ignored, as they don't embed a timestamp.
+    pub fn is_v7_time_sorted(&self) -> bool {
+        self.keys().filter_map(v7_time).is_sorted()
+    }
+
+    /// Merges two maps whose entries are sorted by UUID into a single sorted map.
+    ///
+    /// For maps of UUIDv7s, this means merging two chronologically-ordered maps into
+    /// a single chronologically-ordered one. This is done in linear time, without
+    /// sorting the entries again, and thus requires both maps to already be sorted:
+    /// the order of the result is unspecified otherwise.
+    ///
+    /// If both maps contain the same UUID, the value from `b` is kept.
+    pub fn merge_time_ordered(a: UuidIndexMap<V>, b: UuidIndexMap<V>) -> UuidIndexMap<V> {
+        let mut merged = UuidIndexMap::with_capacity(a.len() + b.len());
+
+        let mut a = a.into_iter().peekable();
+        let mut b = b.into_iter().peekable();
+
+        loop {
+            let entry = match (a.peek(), b.peek()) {
+                (Some((lhs, _)), Some((rhs, _))) if lhs < rhs => a.next(),
+                (Some((lhs, _)), Some((rhs, _))) if lhs == rhs => {
+                    a.next();
+                    b.next()
+                }
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, Some(_)) => b.next(),
+                (None, None) => break,
+            };
+
+            if let Some((uuid, value)) = entry {
+                merged.insert(uuid, value);
+            }
+        }
+
+        merged
+    }
+
+    /// Removes the entries of the given UUIDs while preserving the order of the
+    /// remaining ones, and returns them in the order they had in the map.
+    ///
+    /// This rebuilds the map once, and thus takes `O(n)` time, regardless of the
+    /// number of UUIDs being removed. See [`UuidIndexMap::remove_all_swap()`] for a
+    /// faster alternative which doesn't preserve the order.
+    pub fn remove_all_shift(&mut self, keys: &UuidIndexSet) -> UuidIndexMap<V> {
+        let mut removed = UuidIndexMap::with_capacity(keys.len());
+        let entries = mem::replace(&mut self.0, IndexMap::with_hasher(UuidBuildHasher));
+        self.0.reserve(entries.len().saturating_sub(keys.len()));
+
+        for (uuid, value) in entries {
+            if keys.contains(&uuid) {
+                removed.insert(uuid, value);
+            } else {
+                self.0.insert(uuid, value);
+            }
+        }
+
+        removed
+    }
+
+    /// Removes the entries of the given UUIDs by swapping them with the last entries
+    /// of the map, and returns them in the order of `keys`.
+    ///
+    /// This takes `O(k)` time, where `k` is the number of UUIDs being removed, but
+    /// changes the order of the remaining entries. See
+    /// [`UuidIndexMap::remove_all_shift()`] to preserve it instead.
+    ///
+    /// See [`IndexMap::swap_remove()`].
+    pub fn remove_all_swap(&mut self, keys: &UuidIndexSet) -> UuidIndexMap<V> {
+        keys.iter()
+            .filter_map(|uuid| self.0.swap_remove_entry(uuid))
+            .collect()
+    }
+
+    /// Splits the map into maps of at most `chunk_size` entries, preserving the order
+    /// of its entries.
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if `chunk_size` is `0`.
+    pub fn into_chunks(self, chunk_size: usize) -> Vec<UuidIndexMap<V>> {
+        into_chunks(self.0.into_iter(), chunk_size)
+    }
 }
 
 impl UuidSet {
@@ -169,6 +990,271 @@ impl UuidSet {
     pub fn with_capacity(capacity: usize) -> Self {
         Self(HashSet::with_capacity_and_hasher(capacity, UuidBuildHasher))
     }
+
+    /// Converts the [`UuidSet`] into the [`HashSet`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> HashSet<Uuid, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`HashSet`] wrapped by the [`UuidSet`].
+    #[inline]
+    pub fn as_inner(&self) -> &HashSet<Uuid, UuidBuildHasher> {
+        &self.0
+    }
+
+    /// Inserts a UUID, unless it can't be hashed by [`UuidHasher`], and returns
+    /// whether it wasn't already in the set.
+    ///
+    /// Unlike [`HashSet::insert()`], this doesn't panic.
+    pub fn try_insert(&mut self, uuid: Uuid) -> Result<bool, UnsupportedVersionError> {
+        if !UuidHasher::supports(&uuid) {
+            return Err(UnsupportedVersionError(uuid));
+        }
+
+        Ok(self.0.insert(uuid))
+    }
+
+    /// Creates a [`UuidSet`] of `count` UUIDv7s, the first one having `start` as its
+    /// timestamp and each of the others being `step` after the previous one.
+    ///
+    /// The random bits of the UUIDs are generated deterministically from their
+    /// timestamp and their position, so that this always returns the same set for
+    /// the same arguments. This is meant to be used to create test fixtures.
+    ///
+    /// UUIDv7 timestamps have a millisecond precision, so the timestamps are
+    /// truncated to the millisecond and UUIDs less than a millisecond apart share the
+    /// same timestamp. The UUIDs are always distinct, as their position is encoded in
+    /// their random bits.
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if `start` is before the Unix epoch, or if the timestamp of
+    /// the last UUID overflows.
+    pub fn sequential_v7(start: SystemTime, count: usize, step: Duration) -> Self {
+        let start = start.duration_since(UNIX_EPOCH).unwrap();
+
+        (0..count)
+            .map(|idx| {
+                let time = u32::try_from(idx)
+                    .ok()
+                    .and_then(|idx| step.checked_mul(idx))
+                    .and_then(|offset| start.checked_add(offset))
+                    .expect("the timestamp of the UUIDs overflowed");
+                let millis = time.as_millis() as u64;
+
+                // The first 2 bits of `random[2]` are overwritten by the variant, which
+                // only truncates positions above `2^62`.
+                let mut random = [0; 10];
+                random[..2].copy_from_slice(&(hasher::avalanche(millis) as u16).to_be_bytes());
+                random[2..].copy_from_slice(&(idx as u64).to_be_bytes());
+
+                uuid::Builder::from_unix_timestamp_millis(millis, &random).into_uuid()
+            })
+            .collect()
+    }
+
+    /// Inserts the UUIDs of `other` into the set, and returns the ones which weren't
+    /// already in it.
+    pub fn merge_reporting_new(&mut self, other: &UuidSet) -> UuidSet {
+        let mut new = UuidSet::new();
+        for uuid in other.iter() {
+            if self.0.insert(*uuid) {
+                new.insert(*uuid);
+            }
+        }
+
+        new
+    }
+
+    /// Returns the union of the given sets.
+    ///
+    /// This consumes the sets, reusing the largest one and reserving enough capacity
+    /// for the others before inserting their UUIDs, instead of reallocating
+    /// repeatedly as with successive unions.
+    pub fn union_all(sets: impl IntoIterator<Item = UuidSet>) -> UuidSet {
+        let mut sets: Vec<_> = sets.into_iter().collect();
+
+        let Some(largest) = sets.iter().enumerate().max_by_key(|(_, set)| set.len()) else {
+            return UuidSet::new();
+        };
+
+        let mut union = sets.swap_remove(largest.0);
+        union.reserve(sets.iter().map(|set| set.len()).sum());
+
+        for set in sets {
+            union.0.extend(set.0);
+        }
+
+        union
+    }
+
+    /// Returns the UUIDs which are in at least `n` of the given sets.
+    pub fn majority(sets: &[&UuidSet], n: usize) -> UuidSet {
+        let mut counts = UuidMap::<usize>::new();
+        for uuid in sets.iter().flat_map(|set| set.iter()) {
+            *counts.entry(*uuid).or_default() += 1;
+        }
+
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count >= n)
+            .map(|(uuid, _)| uuid)
+            .collect()
+    }
+
+    /// Returns the bytes of the UUIDs of the set as contiguous 16-byte-aligned blocks,
+    /// suitable for SIMD processing.
+    ///
+    /// This allocates a new vector, in the iteration order of the set.
+    pub fn keys_blocks(&self) -> Vec<UuidBlock> {
+        self.iter()
+            .map(|uuid| UuidBlock(uuid.into_bytes()))
+            .collect()
+    }
+
+    /// Returns an iterator visiting the UUIDv7s of the set with an embedded timestamp
+    /// in `start..end`, in arbitrary order.
+    ///
+    /// UUIDs of other versions are skipped, as they don't embed a timestamp.
+    pub fn range_by_time(&self, start: SystemTime, end: SystemTime) -> impl Iterator<Item = &Uuid> {
+        self.iter()
+            .filter(move |uuid| v7_time(uuid).is_some_and(|time| (start..end).contains(&time)))
+    }
+
+    /// Returns the UUIDv7s of the set whose embedded timestamp is after `now`.
+    ///
+    /// This is useful to detect producers with skewed clocks.
+    pub fn future_keys(&self, now: SystemTime) -> UuidSet {
+        self.iter()
+            .filter(|uuid| v7_time(uuid).is_some_and(|time| time > now))
+            .copied()
+            .collect()
+    }
+
+    /// Groups the UUIDv7s of the set by the millisecond of their embedded timestamp.
+    ///
+    /// UUIDs of other versions are excluded.
+    pub fn group_by_millisecond(&self) -> HashMap<u64, Vec<Uuid>> {
+        let mut groups = HashMap::<_, Vec<_>>::new();
+
+        for uuid in self.iter().filter(|uuid| uuid.get_version_num() == 7) {
+            // The first 48 bits of UUIDv7s are the number of milliseconds since the Unix
+            // epoch.
+            let mut millis = [0; 8];
+            millis[2..].copy_from_slice(&uuid.as_bytes()[..6]);
+
+            groups
+                .entry(u64::from_be_bytes(millis))
+                .or_default()
+                .push(*uuid);
+        }
+
+        groups
+    }
+
+    /// Returns the number of distinct milliseconds embedded in the UUIDv7s of the
+    /// set, i.e. the number of distinct 48-bit timestamps.
+    ///
+    /// UUIDs of other versions are ignored.
+    pub fn distinct_v7_milliseconds(&self) -> usize {
+        self.iter()
+            .filter(|uuid| uuid.get_version_num() == 7)
+            .map(|uuid| uuid.as_u128() >> 80)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Returns the mean duration between the embedded timestamps of consecutive
+    /// UUIDv7s of the set, or `None` if it contains less than two UUIDv7s.
+    ///
+    /// UUIDs of other versions are ignored. The mean of the gaps between sorted
+    /// timestamps is the span between the oldest and newest ones divided by the
+    /// number of gaps, so this doesn't need to sort them.
+    pub fn mean_v7_interarrival(&self) -> Option<Duration> {
+        let mut times = self.iter().filter_map(v7_time);
+
+        let first = times.next()?;
+        let (oldest, newest, count) = times
+            .fold((first, first, 1), |(oldest, newest, count), time| {
+                (oldest.min(time), newest.max(time), count + 1)
+            });
+
+        if count < 2 {
+            return None;
+        }
+
+        let span = newest.duration_since(oldest).unwrap_or_default();
+        Some(span.div_f64((count - 1) as f64))
+    }
+
+    /// Returns the UUIDs of the set, sorted and concatenated.
+    ///
+    /// Two equal sets always produce the same bytes, regardless of the order in
+    /// which their UUIDs were inserted.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut uuids: Vec<_> = self.iter().collect();
+        uuids.sort_unstable();
+
+        uuids
+            .into_iter()
+            .flat_map(|uuid| *uuid.as_bytes())
+            .collect()
+    }
+
+    /// Creates a [`UuidSet`] from bytes produced by [`UuidSet::to_canonical_bytes()`].
+    ///
+    /// This fails if the UUIDs aren't strictly sorted, or if any of them can't be
+    /// hashed by [`UuidHasher`].
+    pub fn from_canonical_bytes(bytes: &[u8]) -> Result<Self, CanonicalBytesError> {
+        if !bytes.len().is_multiple_of(16) {
+            return Err(CanonicalBytesError::InvalidLength(bytes.len()));
+        }
+
+        let mut set = Self::with_capacity(bytes.len() / 16);
+        let mut previous = None;
+
+        for chunk in bytes.chunks_exact(16) {
+            let uuid = Uuid::from_slice(chunk).unwrap();
+
+            if previous.is_some_and(|previous| previous >= uuid) {
+                return Err(CanonicalBytesError::Unsorted(uuid));
+            }
+
+            if !UuidHasher::supports(&uuid) {
+                return Err(CanonicalBytesError::Unsupported(uuid));
+            }
+
+            set.insert(uuid);
+            previous = Some(uuid);
+        }
+
+        Ok(set)
+    }
+
+    /// Returns a fingerprint of the set's contents, which doesn't depend on the order
+    /// in which UUIDs were inserted.
+    ///
+    /// Two equal sets always have the same fingerprint, while two different sets are
+    /// very likely to have different ones.
+    pub fn fingerprint(&self) -> u64 {
+        // The hash of each UUID is avalanched before being XORed, so that similar hashes
+        // don't cancel each other out.
+        self.iter().fold(0, |fingerprint, uuid| {
+            fingerprint ^ hasher::avalanche(UuidBuildHasher.hash_one(uuid))
+        })
+    }
+
+    /// Returns whether the set differs from the one whose fingerprint is
+    /// `other_fingerprint`, e.g. a set on another machine.
+    ///
+    /// This only compares fingerprints (see [`UuidSet::fingerprint()`]): a `false`
+    /// result means that both sets are very likely equal, without having to send
+    /// one of them.
+    #[inline]
+    pub fn estimate_difference(&self, other_fingerprint: u64) -> bool {
+        self.fingerprint() != other_fingerprint
+    }
 }
 
 impl UuidIndexSet {
@@ -190,6 +1276,132 @@ impl UuidIndexSet {
             UuidBuildHasher,
         ))
     }
+
+    /// Converts the [`UuidIndexSet`] into the [`IndexSet`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> IndexSet<Uuid, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`IndexSet`] wrapped by the [`UuidIndexSet`].
+    #[inline]
+    pub fn as_inner(&self) -> &IndexSet<Uuid, UuidBuildHasher> {
+        &self.0
+    }
+
+    /// Returns the UUIDs of the set, sorted and prefix-compressed.
+    ///
+    /// Each UUID is encoded as the number of leading bytes it shares with the previous
+    /// one, followed by its remaining bytes. Since UUIDv7s start with their
+    /// timestamp, UUIDv7s generated close to each other share long prefixes, making
+    /// this much smaller than [`UuidSet::to_canonical_bytes()`] for such sets.
+    pub fn to_prefix_compressed(&self) -> Vec<u8> {
+        let mut uuids: Vec<_> = self.iter().collect();
+        uuids.sort_unstable();
+
+        let mut bytes = Vec::with_capacity(uuids.len() * 17);
+        let mut previous = &[0; 16];
+
+        for (idx, uuid) in uuids.into_iter().enumerate() {
+            let uuid = uuid.as_bytes();
+            let shared = match idx {
+                0 => 0,
+                _ => uuid
+                    .iter()
+                    .zip(previous)
+                    .take_while(|(a, b)| a == b)
+                    .count(),
+            };
+
+            bytes.push(shared as u8);
+            bytes.extend_from_slice(&uuid[shared..]);
+            previous = uuid;
+        }
+
+        bytes
+    }
+
+    /// Creates a [`UuidIndexSet`] from bytes produced by
+    /// [`UuidIndexSet::to_prefix_compressed()`], with its UUIDs in sorted order.
+    ///
+    /// This fails if the bytes are malformed, if the UUIDs aren't strictly sorted, or
+    /// if any of them can't be hashed by [`UuidHasher`].
+    pub fn from_prefix_compressed(mut bytes: &[u8]) -> Result<Self, PrefixCompressedError> {
+        let mut set = Self::new();
+        let mut previous: Option<Uuid> = None;
+
+        while let Some((&shared, rest)) = bytes.split_first() {
+            let shared = shared as usize;
+            if shared > 15 || (previous.is_none() && shared != 0) {
+                return Err(PrefixCompressedError::InvalidPrefixLength(shared as u8));
+            }
+
+            let Some((suffix, rest)) = rest.split_at_checked(16 - shared) else {
+                return Err(PrefixCompressedError::Truncated);
+            };
+
+            let mut uuid = previous.map_or([0; 16], Uuid::into_bytes);
+            uuid[shared..].copy_from_slice(suffix);
+            let uuid = Uuid::from_bytes(uuid);
+
+            if previous.is_some_and(|previous| previous >= uuid) {
+                return Err(PrefixCompressedError::Unsorted(uuid));
+            }
+
+            if !UuidHasher::supports(&uuid) {
+                return Err(PrefixCompressedError::Unsupported(uuid));
+            }
+
+            set.insert(uuid);
+            previous = Some(uuid);
+            bytes = rest;
+        }
+
+        Ok(set)
+    }
+}
+
+/// Returns a hash of the given items which doesn't depend on their order, by
+/// XOR-ing their hashes.
+///
+/// This is used to hash collections whose equality doesn't depend on the order of
+/// their items.
+pub(crate) fn unordered_hash<T: Hash>(items: impl Iterator<Item = T>) -> u64 {
+    items.fold(0, |hash, item| {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        hash ^ hasher.finish()
+    })
+}
+
+/// Returns the time embedded in the given UUID if it is a UUIDv7.
+pub(crate) fn v7_time(uuid: &Uuid) -> Option<SystemTime> {
+    if uuid.get_version_num() != 7 {
+        return None;
+    }
+
+    let (secs, nanos) = uuid.get_timestamp()?.to_unix();
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// Collects the given items into collections of at most `chunk_size` items each.
+///
+/// ## Panics
+///
+/// This will panic if `chunk_size` is `0`.
+fn into_chunks<I, C>(mut items: I, chunk_size: usize) -> Vec<C>
+where
+    I: ExactSizeIterator,
+    C: FromIterator<I::Item>,
+{
+    assert_ne!(chunk_size, 0, "chunk size must be non-zero");
+
+    let mut chunks = Vec::with_capacity(items.len().div_ceil(chunk_size));
+    while items.len() != 0 {
+        chunks.push(items.by_ref().take(chunk_size).collect());
+    }
+
+    chunks
 }
 
 impl<V> Default for UuidMap<V> {
@@ -234,6 +1446,64 @@ impl From<IndexSet<Uuid, UuidBuildHasher>> for UuidIndexSet {
     }
 }
 
+/// Creates a [`UuidMap`] from an array of entries.
+///
+/// ## Examples
+///
+/// ```
+/// # use uuid::Uuid;
+/// # use uuid_collections::UuidMap;
+/// let (id, id2) = (Uuid::new_v4(), Uuid::new_v4());
+/// let map = UuidMap::from([(id, 1), (id2, 2)]);
+///
+/// assert_eq!(map[&id2], 2);
+/// ```
+impl<V, const N: usize> From<[(Uuid, V); N]> for UuidMap<V> {
+    fn from(entries: [(Uuid, V); N]) -> Self {
+        let mut map = Self::with_capacity(N);
+        map.extend(entries);
+        map
+    }
+}
+
+/// Creates a [`UuidIndexMap`] from an array of entries, in the same order.
+impl<V, const N: usize> From<[(Uuid, V); N]> for UuidIndexMap<V> {
+    fn from(entries: [(Uuid, V); N]) -> Self {
+        let mut map = Self::with_capacity(N);
+        map.extend(entries);
+        map
+    }
+}
+
+/// Creates a [`UuidSet`] from an array of UUIDs.
+///
+/// ## Examples
+///
+/// ```
+/// # use uuid::Uuid;
+/// # use uuid_collections::UuidSet;
+/// let (id, id2) = (Uuid::new_v4(), Uuid::new_v4());
+/// let set = UuidSet::from([id, id2]);
+///
+/// assert!(set.contains(&id));
+/// ```
+impl<const N: usize> From<[Uuid; N]> for UuidSet {
+    fn from(uuids: [Uuid; N]) -> Self {
+        let mut set = Self::with_capacity(N);
+        set.extend(uuids);
+        set
+    }
+}
+
+/// Creates a [`UuidIndexSet`] from an array of UUIDs, in the same order.
+impl<const N: usize> From<[Uuid; N]> for UuidIndexSet {
+    fn from(uuids: [Uuid; N]) -> Self {
+        let mut set = Self::with_capacity(N);
+        set.extend(uuids);
+        set
+    }
+}
+
 impl<V> From<UuidMap<V>> for HashMap<Uuid, V, UuidBuildHasher> {
     #[inline]
     fn from(map: UuidMap<V>) -> Self {
@@ -350,6 +1620,38 @@ impl Debug for UuidIndexSet {
     }
 }
 
+impl<V: Hash> Hash for UuidMap<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
+/// Like its [`PartialEq`] implementation, this doesn't depend on the order of the
+/// entries.
+impl<V: Hash> Hash for UuidIndexMap<V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
+impl Hash for UuidSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
+/// Like its [`PartialEq`] implementation, this doesn't depend on the order of the
+/// UUIDs.
+impl Hash for UuidIndexSet {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
 impl<V> Extend<(Uuid, V)> for UuidMap<V> {
     #[inline]
     fn extend<T: IntoIterator<Item = (Uuid, V)>>(&mut self, iter: T) {
@@ -416,6 +1718,41 @@ impl<V> IntoIterator for UuidMap<V> {
     }
 }
 
+/// ## Examples
+///
+/// ```
+/// # use uuid::Uuid;
+/// # use uuid_collections::UuidMap;
+/// let my_map = UuidMap::from([(Uuid::new_v4(), 1), (Uuid::new_v4(), 2)]);
+///
+/// let mut sum = 0;
+/// for (id, v) in &my_map {
+///     assert_eq!(my_map.get(id), Some(v));
+///     sum += v;
+/// }
+///
+/// assert_eq!(sum, 3);
+/// ```
+impl<'a, V> IntoIterator for &'a UuidMap<V> {
+    type Item = (&'a Uuid, &'a V);
+    type IntoIter = hash_map::Iter<'a, Uuid, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a mut UuidMap<V> {
+    type Item = (&'a Uuid, &'a mut V);
+    type IntoIter = hash_map::IterMut<'a, Uuid, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
 impl<V> IntoIterator for UuidIndexMap<V> {
     type Item = (Uuid, V);
     type IntoIter = map::IntoIter<Uuid, V>;
@@ -426,6 +1763,26 @@ impl<V> IntoIterator for UuidIndexMap<V> {
     }
 }
 
+impl<'a, V> IntoIterator for &'a UuidIndexMap<V> {
+    type Item = (&'a Uuid, &'a V);
+    type IntoIter = map::Iter<'a, Uuid, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a mut UuidIndexMap<V> {
+    type Item = (&'a Uuid, &'a mut V);
+    type IntoIter = map::IterMut<'a, Uuid, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
 impl IntoIterator for UuidSet {
     type Item = Uuid;
     type IntoIter = hash_set::IntoIter<Uuid>;
@@ -436,6 +1793,16 @@ impl IntoIterator for UuidSet {
     }
 }
 
+impl<'a> IntoIterator for &'a UuidSet {
+    type Item = &'a Uuid;
+    type IntoIter = hash_set::Iter<'a, Uuid>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
 impl IntoIterator for UuidIndexSet {
     type Item = Uuid;
     type IntoIter = set::IntoIter<Uuid>;
@@ -445,3 +1812,65 @@ impl IntoIterator for UuidIndexSet {
         self.0.into_iter()
     }
 }
+
+impl<'a> IntoIterator for &'a UuidIndexSet {
+    type Item = &'a Uuid;
+    type IntoIter = set::Iter<'a, Uuid>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+macro_rules! impl_set_ops {
+    ($name:ident) => {
+        /// Returns the union of both sets as a new set.
+        impl BitOr<&$name> for &$name {
+            type Output = $name;
+
+            fn bitor(self, rhs: &$name) -> $name {
+                let mut set = $name::with_capacity(self.len().max(rhs.len()));
+                set.extend(self.0.union(&rhs.0).copied());
+                set
+            }
+        }
+
+        /// Returns the intersection of both sets as a new set.
+        impl BitAnd<&$name> for &$name {
+            type Output = $name;
+
+            fn bitand(self, rhs: &$name) -> $name {
+                let mut set = $name::with_capacity(self.len().min(rhs.len()));
+                set.extend(self.0.intersection(&rhs.0).copied());
+                set
+            }
+        }
+
+        /// Returns the UUIDs which are in only one of both sets as a new set.
+        impl BitXor<&$name> for &$name {
+            type Output = $name;
+
+            fn bitxor(self, rhs: &$name) -> $name {
+                let mut set = $name::with_capacity(self.len().max(rhs.len()));
+                set.extend(self.0.symmetric_difference(&rhs.0).copied());
+                set
+            }
+        }
+
+        /// Returns the UUIDs which are in the first set but not in the second one as
+        /// a new set.
+        impl Sub<&$name> for &$name {
+            type Output = $name;
+
+            fn sub(self, rhs: &$name) -> $name {
+                let mut set = $name::with_capacity(self.len());
+                set.extend(self.0.difference(&rhs.0).copied());
+                set
+            }
+        }
+    };
+}
+
+impl_set_ops!(UuidSet);
+impl_set_ops!(UuidIndexSet);
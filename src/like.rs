@@ -1,20 +1,22 @@
 use std::{
     collections::{HashMap, HashSet, hash_map, hash_set},
     fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
     ops::{Deref, DerefMut},
 };
 
 use indexmap::{IndexMap, IndexSet, map, set};
+use uuid::Uuid;
 use uuid_like::UuidLike;
 
-use crate::UuidBuildHasher;
+use crate::{UuidBuildHasher, UuidMap, unordered_hash};
 
 /// A [`UuidMap`][1] for UUID-like keys which implement [`UuidLike`].
 ///
 /// See [`UuidMap`][1] and [`UuidLike`] for more details.
 ///
 /// [1]: crate::UuidMap
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub struct UuidLikeMap<K: UuidLike, V>(HashMap<K, V, UuidBuildHasher>);
 
@@ -23,7 +25,7 @@ pub struct UuidLikeMap<K: UuidLike, V>(HashMap<K, V, UuidBuildHasher>);
 /// See [`UuidIndexMap`][1] and [`UuidLike`] for more details.
 ///
 /// [1]: crate::UuidIndexMap
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub struct UuidLikeIndexMap<K: UuidLike, V>(IndexMap<K, V, UuidBuildHasher>);
 
@@ -32,7 +34,7 @@ pub struct UuidLikeIndexMap<K: UuidLike, V>(IndexMap<K, V, UuidBuildHasher>);
 /// See [`UuidSet`][1] and [`UuidLike`] for more details.
 ///
 /// [1]: crate::UuidSet
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub struct UuidLikeSet<K: UuidLike>(HashSet<K, UuidBuildHasher>);
 
@@ -41,7 +43,7 @@ pub struct UuidLikeSet<K: UuidLike>(HashSet<K, UuidBuildHasher>);
 /// See [`UuidIndexSet`][1] and [`UuidLike`] for more details.
 ///
 /// [1]: crate::UuidSet
-#[cfg_attr(feature = "serde-1", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde-1", derive(serde::Serialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub struct UuidLikeIndexSet<K: UuidLike>(IndexSet<K, UuidBuildHasher>);
 
@@ -62,6 +64,36 @@ impl<K: UuidLike, V> UuidLikeMap<K, V> {
         Self(HashMap::with_capacity_and_hasher(capacity, UuidBuildHasher))
     }
 
+    /// Converts the [`UuidLikeMap`] into the [`HashMap`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> HashMap<K, V, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`HashMap`] wrapped by the [`UuidLikeMap`].
+    #[inline]
+    pub fn as_inner(&self) -> &HashMap<K, V, UuidBuildHasher> {
+        &self.0
+    }
+
+    /// Returns a [`UuidLikeSet`] containing the keys of the map.
+    pub fn key_set(&self) -> UuidLikeSet<K>
+    where
+        K: Clone,
+    {
+        let mut set = UuidLikeSet::with_capacity(self.len());
+        set.extend(self.keys().cloned());
+        set
+    }
+
+    /// Converts the map into a [`UuidMap`], keyed by the UUIDs of its keys.
+    pub fn into_uuid_map(self) -> UuidMap<V> {
+        self.0
+            .into_iter()
+            .map(|(key, value)| (Uuid::from_bytes(key.into_bytes()), value))
+            .collect()
+    }
+
     /// Creates a consuming iterator visiting all UUIDs in arbitrary order.
     ///
     /// See [`HashMap::into_keys()`].
@@ -99,7 +131,30 @@ impl<K: UuidLike, V> UuidLikeIndexMap<K, V> {
         ))
     }
 
-    /// Creates a consuming iterator visiting all UUIDs in arbitrary order.
+    /// Converts the [`UuidLikeIndexMap`] into the [`IndexMap`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> IndexMap<K, V, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`IndexMap`] wrapped by the [`UuidLikeIndexMap`].
+    #[inline]
+    pub fn as_inner(&self) -> &IndexMap<K, V, UuidBuildHasher> {
+        &self.0
+    }
+
+    /// Returns a [`UuidLikeIndexSet`] containing the keys of the map, in the same
+    /// order.
+    pub fn key_set(&self) -> UuidLikeIndexSet<K>
+    where
+        K: Clone,
+    {
+        let mut set = UuidLikeIndexSet::with_capacity(self.len());
+        set.extend(self.keys().cloned());
+        set
+    }
+
+    /// Creates a consuming iterator visiting all UUIDs in order.
     ///
     /// See [`IndexMap::into_keys()`].
     #[inline]
@@ -107,7 +162,7 @@ impl<K: UuidLike, V> UuidLikeIndexMap<K, V> {
         self.0.into_keys()
     }
 
-    /// Creates a consuming iterator visiting all values in arbitrary order.
+    /// Creates a consuming iterator visiting all values in order.
     ///
     /// See [`IndexMap::into_values()`].
     #[inline]
@@ -132,6 +187,18 @@ impl<K: UuidLike> UuidLikeSet<K> {
     pub fn with_capacity(capacity: usize) -> Self {
         Self(HashSet::with_capacity_and_hasher(capacity, UuidBuildHasher))
     }
+
+    /// Converts the [`UuidLikeSet`] into the [`HashSet`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> HashSet<K, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`HashSet`] wrapped by the [`UuidLikeSet`].
+    #[inline]
+    pub fn as_inner(&self) -> &HashSet<K, UuidBuildHasher> {
+        &self.0
+    }
 }
 
 impl<K: UuidLike> UuidLikeIndexSet<K> {
@@ -153,6 +220,18 @@ impl<K: UuidLike> UuidLikeIndexSet<K> {
             UuidBuildHasher,
         ))
     }
+
+    /// Converts the [`UuidLikeIndexSet`] into the [`IndexSet`] it wraps.
+    #[inline]
+    pub fn into_inner(self) -> IndexSet<K, UuidBuildHasher> {
+        self.0
+    }
+
+    /// Returns a reference to the [`IndexSet`] wrapped by the [`UuidLikeIndexSet`].
+    #[inline]
+    pub fn as_inner(&self) -> &IndexSet<K, UuidBuildHasher> {
+        &self.0
+    }
 }
 
 impl<K: UuidLike, V> Default for UuidLikeMap<K, V> {
@@ -209,6 +288,38 @@ impl<K: UuidLike> From<IndexSet<K, UuidBuildHasher>> for UuidLikeIndexSet<K> {
     }
 }
 
+impl<K: UuidLike, V, const N: usize> From<[(K, V); N]> for UuidLikeMap<K, V> {
+    fn from(entries: [(K, V); N]) -> Self {
+        let mut map = Self::with_capacity(N);
+        map.extend(entries);
+        map
+    }
+}
+
+impl<K: UuidLike, V, const N: usize> From<[(K, V); N]> for UuidLikeIndexMap<K, V> {
+    fn from(entries: [(K, V); N]) -> Self {
+        let mut map = Self::with_capacity(N);
+        map.extend(entries);
+        map
+    }
+}
+
+impl<K: UuidLike, const N: usize> From<[K; N]> for UuidLikeSet<K> {
+    fn from(keys: [K; N]) -> Self {
+        let mut set = Self::with_capacity(N);
+        set.extend(keys);
+        set
+    }
+}
+
+impl<K: UuidLike, const N: usize> From<[K; N]> for UuidLikeIndexSet<K> {
+    fn from(keys: [K; N]) -> Self {
+        let mut set = Self::with_capacity(N);
+        set.extend(keys);
+        set
+    }
+}
+
 impl<K: UuidLike, V> From<UuidLikeMap<K, V>> for HashMap<K, V, UuidBuildHasher> {
     #[inline]
     fn from(map: UuidLikeMap<K, V>) -> Self {
@@ -325,6 +436,38 @@ impl<K: UuidLike> Debug for UuidLikeIndexSet<K> {
     }
 }
 
+impl<K: UuidLike, V: Hash> Hash for UuidLikeMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
+/// Like its [`PartialEq`] implementation, this doesn't depend on the order of the
+/// entries.
+impl<K: UuidLike, V: Hash> Hash for UuidLikeIndexMap<K, V> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
+impl<K: UuidLike> Hash for UuidLikeSet<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
+/// Like its [`PartialEq`] implementation, this doesn't depend on the order of the
+/// keys.
+impl<K: UuidLike> Hash for UuidLikeIndexSet<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        state.write_u64(unordered_hash(self.iter()));
+    }
+}
+
 impl<K: UuidLike, V> Extend<(K, V)> for UuidLikeMap<K, V> {
     #[inline]
     fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
@@ -391,6 +534,26 @@ impl<K: UuidLike, V> IntoIterator for UuidLikeMap<K, V> {
     }
 }
 
+impl<'a, K: UuidLike, V> IntoIterator for &'a UuidLikeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = hash_map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, K: UuidLike, V> IntoIterator for &'a mut UuidLikeMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = hash_map::IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
 impl<K: UuidLike, V> IntoIterator for UuidLikeIndexMap<K, V> {
     type Item = (K, V);
     type IntoIter = map::IntoIter<K, V>;
@@ -401,6 +564,26 @@ impl<K: UuidLike, V> IntoIterator for UuidLikeIndexMap<K, V> {
     }
 }
 
+impl<'a, K: UuidLike, V> IntoIterator for &'a UuidLikeIndexMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = map::Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, K: UuidLike, V> IntoIterator for &'a mut UuidLikeIndexMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = map::IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
 impl<K: UuidLike> IntoIterator for UuidLikeSet<K> {
     type Item = K;
     type IntoIter = hash_set::IntoIter<K>;
@@ -411,6 +594,16 @@ impl<K: UuidLike> IntoIterator for UuidLikeSet<K> {
     }
 }
 
+impl<'a, K: UuidLike> IntoIterator for &'a UuidLikeSet<K> {
+    type Item = &'a K;
+    type IntoIter = hash_set::Iter<'a, K>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
 impl<K: UuidLike> IntoIterator for UuidLikeIndexSet<K> {
     type Item = K;
     type IntoIter = set::IntoIter<K>;
@@ -420,3 +613,13 @@ impl<K: UuidLike> IntoIterator for UuidLikeIndexSet<K> {
         self.0.into_iter()
     }
 }
+
+impl<'a, K: UuidLike> IntoIterator for &'a UuidLikeIndexSet<K> {
+    type Item = &'a K;
+    type IntoIter = set::Iter<'a, K>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+};
+
+use uuid::Uuid;
+
+use crate::UuidMap;
+
+/// A [`UuidMap`] which maps each UUID to several values.
+///
+/// The values of each UUID are kept in the order they were inserted in. UUIDs without
+/// any values aren't kept in the map.
+///
+/// See [`UuidMultiMap::insert()`].
+pub struct UuidMultiMap<V> {
+    map: UuidMap<Vec<V>>,
+    // The total number of values, across all the UUIDs.
+    values: usize,
+}
+
+impl<V> UuidMultiMap<V> {
+    /// Creates an empty [`UuidMultiMap`].
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            map: UuidMap::new(),
+            values: 0,
+        }
+    }
+
+    /// Returns the total number of values stored in the map, across all the UUIDs.
+    #[inline]
+    pub fn len_values(&self) -> usize {
+        self.values
+    }
+
+    /// Returns the values of the given UUID, in the order they were inserted in.
+    ///
+    /// This is empty if the map doesn't contain the UUID.
+    pub fn get_all(&self, uuid: &Uuid) -> &[V] {
+        self.map.get(uuid).map_or(&[], Vec::as_slice)
+    }
+
+    /// Appends a value to the values of the given UUID.
+    pub fn insert(&mut self, uuid: Uuid, value: V) {
+        self.map.entry(uuid).or_default().push(value);
+        self.values += 1;
+    }
+
+    /// Removes the first value of the given UUID which is equal to `value`, and
+    /// returns whether there was one.
+    ///
+    /// The UUID is removed from the map once it doesn't have any values left.
+    pub fn remove_value(&mut self, uuid: &Uuid, value: &V) -> bool
+    where
+        V: PartialEq,
+    {
+        let Some(values) = self.map.get_mut(uuid) else {
+            return false;
+        };
+
+        let Some(idx) = values.iter().position(|other| other == value) else {
+            return false;
+        };
+
+        values.remove(idx);
+        if values.is_empty() {
+            self.map.remove(uuid);
+        }
+
+        self.values -= 1;
+        true
+    }
+}
+
+impl<V> Default for UuidMultiMap<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Deref for UuidMultiMap<V> {
+    type Target = UuidMap<Vec<V>>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<V: Debug> Debug for UuidMultiMap<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}
+
+impl<V> Extend<(Uuid, V)> for UuidMultiMap<V> {
+    fn extend<T: IntoIterator<Item = (Uuid, V)>>(&mut self, iter: T) {
+        for (uuid, value) in iter {
+            self.insert(uuid, value);
+        }
+    }
+}
+
+impl<V> FromIterator<(Uuid, V)> for UuidMultiMap<V> {
+    fn from_iter<T: IntoIterator<Item = (Uuid, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
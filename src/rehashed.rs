@@ -0,0 +1,20 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::{FallbackUuidBuildHasher, UuidMap};
+
+/// The result of [`UuidMap::rehash_colliding_with_fallback()`][1].
+///
+/// [1]: crate::UuidMap::rehash_colliding_with_fallback
+#[derive(Clone)]
+pub enum Rehashed<V> {
+    /// The map didn't have entropy collisions, and was returned unchanged.
+    Unchanged(UuidMap<V>),
+
+    /// The map had entropy collisions, and its entries were rehashed using
+    /// [`FallbackUuidHasher`][1].
+    ///
+    /// [1]: crate::FallbackUuidHasher
+    Fallback(HashMap<Uuid, V, FallbackUuidBuildHasher>),
+}
@@ -0,0 +1,18 @@
+/// Statistics about the building of a [`UuidMap`][1].
+///
+/// See [`UuidMap::from_iter_with_stats()`][2].
+///
+/// [1]: crate::UuidMap
+/// [2]: crate::UuidMap::from_iter_with_stats
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct BuildStats {
+    /// The number of entries which were inserted, including the ones which
+    /// overwrote a previous entry.
+    pub inserted: usize,
+
+    /// The number of entries which overwrote a previous entry with the same UUID.
+    pub overwritten: usize,
+
+    /// The capacity of the map once built.
+    pub capacity: usize,
+}
@@ -1,7 +1,17 @@
-use std::array;
+use std::{
+    array,
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use uuid::{NoContext, Timestamp};
 
 use super::*;
 
+/// A UUIDv7 with an invalid variant, which is unsupported with or without `gxhash-3`.
+const UNSUPPORTED: Uuid = Uuid::from_u128(0x0190_0000_0000_7000_c000_0000_0000_0000);
+
 #[test]
 fn v4() {
     let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
@@ -68,6 +78,72 @@ fn v7() {
     assert!(!set.contains(&uuids[2]));
 }
 
+#[test]
+fn index_v4() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut map = UuidIndexMap::new();
+    let mut set = UuidIndexSet::new();
+
+    map.insert(uuids[0], 0);
+    map.insert(uuids[2], 2);
+
+    set.insert(uuids[0]);
+    set.insert(uuids[2]);
+
+    assert_eq!(map.get(&uuids[0]), Some(&0));
+    assert!(!map.contains_key(&uuids[1]));
+    assert_eq!(map.get(&uuids[2]), Some(&2));
+
+    assert!(set.contains(&uuids[0]));
+    assert!(!set.contains(&uuids[1]));
+    assert!(set.contains(&uuids[2]));
+
+    map.shift_remove(&uuids[2]);
+    set.shift_remove(&uuids[2]);
+
+    assert_eq!(map.get(&uuids[0]), Some(&0));
+    assert!(!map.contains_key(&uuids[1]));
+    assert!(!map.contains_key(&uuids[2]));
+
+    assert!(set.contains(&uuids[0]));
+    assert!(!set.contains(&uuids[1]));
+    assert!(!set.contains(&uuids[2]));
+}
+
+#[test]
+fn index_v7() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::now_v7());
+
+    let mut map = UuidIndexMap::new();
+    let mut set = UuidIndexSet::new();
+
+    map.insert(uuids[0], 0);
+    map.insert(uuids[2], 2);
+
+    set.insert(uuids[0]);
+    set.insert(uuids[2]);
+
+    assert_eq!(map.get(&uuids[0]), Some(&0));
+    assert!(!map.contains_key(&uuids[1]));
+    assert_eq!(map.get(&uuids[2]), Some(&2));
+
+    assert!(set.contains(&uuids[0]));
+    assert!(!set.contains(&uuids[1]));
+    assert!(set.contains(&uuids[2]));
+
+    map.shift_remove(&uuids[2]);
+    set.shift_remove(&uuids[2]);
+
+    assert_eq!(map.get(&uuids[0]), Some(&0));
+    assert!(!map.contains_key(&uuids[1]));
+    assert!(!map.contains_key(&uuids[2]));
+
+    assert!(set.contains(&uuids[0]));
+    assert!(!set.contains(&uuids[1]));
+    assert!(!set.contains(&uuids[2]));
+}
+
 #[test]
 #[should_panic]
 fn map_v1() {
@@ -85,3 +161,2211 @@ fn set_v1() {
     let mut set = UuidSet::new();
     set.insert(uuid);
 }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct PackedKey(u64);
+
+impl UuidKey for PackedKey {
+    fn version(&self) -> u8 {
+        4
+    }
+
+    fn hash_bytes(&self) -> [u8; 8] {
+        self.0.to_be_bytes()
+    }
+}
+
+impl Hash for PackedKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash_into(state);
+    }
+}
+
+#[test]
+fn custom_key() {
+    let keys = [PackedKey(1), PackedKey(2), PackedKey(u64::MAX)];
+
+    let mut map = HashMap::with_hasher(UuidBuildHasher);
+    map.insert(keys[0], 0);
+    map.insert(keys[2], 2);
+
+    assert_eq!(map.get(&keys[0]), Some(&0));
+    assert!(!map.contains_key(&keys[1]));
+    assert_eq!(map.get(&keys[2]), Some(&2));
+}
+
+uuid_like::wrapper!(UserId);
+
+#[test]
+fn uuid_like_key() {
+    use uuid_like::UuidLike;
+
+    let uuids = [Uuid::new_v4(), Uuid::now_v7()];
+
+    for uuid in uuids {
+        let key = UuidLikeKey(UserId::from_bytes(uuid.into_bytes()));
+        assert_eq!(key.version(), uuid.version());
+        assert_eq!(key.hash_bytes(), uuid.hash_bytes());
+    }
+
+    let keys = uuids.map(|uuid| UuidLikeKey(UserId::from_bytes(uuid.into_bytes())));
+
+    let mut map = HashMap::with_hasher(UuidBuildHasher);
+    map.insert(keys[0], 0);
+
+    assert_eq!(map.get(&keys[0]), Some(&0));
+    assert!(!map.contains_key(&keys[1]));
+}
+
+#[test]
+fn into_uuid_map() {
+    use uuid_like::UuidLike;
+
+    let uuids = [Uuid::new_v4(), Uuid::now_v7(), Uuid::new_v4()];
+
+    let mut map = UuidLikeMap::new();
+    for (uuid, idx) in uuids.iter().zip(0..) {
+        map.insert(UserId::from_bytes(uuid.into_bytes()), idx);
+    }
+
+    let map = map.into_uuid_map();
+    assert_eq!(map.len(), 3);
+    assert!(uuids.iter().zip(0..).all(|(uuid, idx)| map[uuid] == idx));
+}
+
+#[test]
+fn columns() {
+    let keys: Vec<_> = (0..16).map(|_| Uuid::new_v4()).collect();
+    let values: Vec<_> = (0..16).collect();
+
+    let map = UuidMap::from_columns(keys.clone(), values.clone());
+    assert_eq!(map.len(), 16);
+
+    let (out_keys, out_values) = map.into_parts();
+    assert_eq!(out_keys.len(), 16);
+    assert_eq!(out_values.len(), 16);
+
+    for (key, value) in out_keys.iter().zip(&out_values) {
+        let idx = keys.iter().position(|k| k == key).unwrap();
+        assert_eq!(values[idx], *value);
+    }
+}
+
+#[cfg(feature = "rand-09")]
+#[test]
+fn sample_k() {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    let set: UuidSet = (0..10).map(|_| Uuid::new_v4()).collect();
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut counts = UuidMap::new();
+
+    for _ in 0..10_000 {
+        let sample = set.sample_k(3, &mut rng);
+        assert_eq!(sample.len(), 3);
+
+        for uuid in sample {
+            *counts.entry(uuid).or_insert(0) += 1;
+        }
+    }
+
+    // Each UUID is expected to be sampled 3,000 times.
+    assert_eq!(counts.len(), 10);
+    assert!(counts.values().all(|count| (2_700..=3_300).contains(count)));
+
+    assert_eq!(set.sample_k(20, &mut rng).len(), 10);
+    assert!(set.sample_k(0, &mut rng).is_empty());
+}
+
+#[cfg(all(feature = "rand-09", feature = "rkyv-08"))]
+#[test]
+fn sample_agrees_with_archived() {
+    use rand::{SeedableRng, rngs::StdRng};
+    use rkyv::{Archive, rancor};
+
+    let mut map: UuidMap<u32> = (0..100).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    // SAFETY: `bytes` was just produced by serializing a `UuidMap`.
+    let archived = unsafe { rkyv::access_unchecked::<<UuidMap<u32> as Archive>::Archived>(&bytes) };
+
+    assert!(map.sample_agrees_with_archived(archived, 10, &mut rng));
+
+    *map.values_mut().next().unwrap() += 1;
+    assert!(map.sample_agrees_with_archived(archived, 0, &mut rng));
+    assert!(!map.sample_agrees_with_archived(archived, 100, &mut rng));
+
+    map.insert(Uuid::new_v4(), 0);
+    assert!(!map.sample_agrees_with_archived(archived, 0, &mut rng));
+}
+
+#[test]
+fn distinct_v7_milliseconds() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut set = UuidSet::sequential_v7(start, 4, Duration::from_millis(1));
+    set.extend(UuidSet::sequential_v7(start, 3, Duration::from_micros(100)));
+    set.insert(Uuid::new_v4());
+
+    assert_eq!(set.len(), 7);
+    assert_eq!(set.distinct_v7_milliseconds(), 4);
+    assert_eq!(UuidSet::new().distinct_v7_milliseconds(), 0);
+}
+
+#[test]
+fn mean_v7_interarrival() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut set = UuidSet::sequential_v7(start, 10, Duration::from_millis(250));
+    set.insert(Uuid::new_v4());
+
+    assert_eq!(set.mean_v7_interarrival(), Some(Duration::from_millis(250)));
+
+    let set = UuidSet::sequential_v7(start, 1, Duration::from_millis(250));
+    assert_eq!(set.mean_v7_interarrival(), None);
+    assert_eq!(UuidSet::new().mean_v7_interarrival(), None);
+}
+
+#[test]
+fn prefix_compressed() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut set: UuidIndexSet = UuidSet::sequential_v7(start, 100, Duration::from_millis(1))
+        .iter()
+        .copied()
+        .collect();
+    set.insert(Uuid::new_v4());
+
+    let bytes = set.to_prefix_compressed();
+    assert!(bytes.len() < set.len() * 16);
+
+    let decoded = UuidIndexSet::from_prefix_compressed(&bytes).unwrap();
+    assert_eq!(decoded.len(), set.len());
+    assert!(decoded.iter().is_sorted());
+    assert!(decoded.iter().all(|uuid| set.contains(uuid)));
+
+    assert_eq!(
+        UuidIndexSet::from_prefix_compressed(&bytes[..bytes.len() - 1]),
+        Err(PrefixCompressedError::Truncated),
+    );
+    assert_eq!(
+        UuidIndexSet::from_prefix_compressed(&[1]),
+        Err(PrefixCompressedError::InvalidPrefixLength(1)),
+    );
+    assert!(
+        UuidIndexSet::from_prefix_compressed(&[])
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn merge_reporting_new() {
+    let uuids: [_; 5] = array::from_fn(|_| Uuid::new_v4());
+    let mut set = UuidSet::from([uuids[0], uuids[1]]);
+
+    let new = set.merge_reporting_new(&UuidSet::from([uuids[1], uuids[2]]));
+    assert_eq!(new, UuidSet::from([uuids[2]]));
+
+    let new = set.merge_reporting_new(&UuidSet::from([uuids[3], uuids[4]]));
+    assert_eq!(new, UuidSet::from([uuids[3], uuids[4]]));
+
+    assert!(
+        set.merge_reporting_new(&UuidSet::from([uuids[0]]))
+            .is_empty()
+    );
+    assert_eq!(set, UuidSet::from(uuids));
+}
+
+#[test]
+fn union_all() {
+    let uuids: [_; 6] = array::from_fn(|_| Uuid::new_v4());
+    let sets = [&uuids[..3], &uuids[2..4], &uuids[3..], &[]]
+        .map(|uuids| uuids.iter().copied().collect::<UuidSet>());
+
+    let union = UuidSet::union_all(sets);
+    assert_eq!(union.len(), 6);
+    assert!(uuids.iter().all(|uuid| union.contains(uuid)));
+
+    assert!(UuidSet::union_all([]).is_empty());
+}
+
+#[test]
+fn majority() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+    let sets = [&uuids[..2], &uuids[1..3], &uuids[2..]]
+        .map(|uuids| uuids.iter().copied().collect::<UuidSet>());
+
+    let majority = UuidSet::majority(&[&sets[0], &sets[1], &sets[2]], 2);
+    assert_eq!(majority, uuids[1..3].iter().copied().collect::<UuidSet>());
+
+    assert!(UuidSet::majority(&[&sets[0], &sets[1], &sets[2]], 3).is_empty());
+    assert_eq!(
+        UuidSet::majority(&[&sets[0], &sets[1], &sets[2]], 1).len(),
+        4
+    );
+}
+
+#[test]
+fn future_keys() {
+    let now = SystemTime::now();
+    let secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    let past = Uuid::now_v7();
+    let future = Uuid::new_v7(Timestamp::from_unix(NoContext, secs + 3_600, 0));
+
+    let set: UuidSet = [past, future, Uuid::new_v4()].into_iter().collect();
+    let future_keys = set.future_keys(now);
+
+    assert_eq!(future_keys.len(), 1);
+    assert!(future_keys.contains(&future));
+}
+
+#[test]
+fn swap() {
+    let uuids: [_; 2] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut front = UuidMap::new();
+    let mut back = UuidMap::new();
+
+    front.insert(uuids[0], 0);
+    back.insert(uuids[1], 1);
+    back.insert(uuids[0], 2);
+
+    front.swap(&mut back);
+
+    assert_eq!(front.len(), 2);
+    assert_eq!(front.get(&uuids[0]), Some(&2));
+    assert_eq!(front.get(&uuids[1]), Some(&1));
+
+    assert_eq!(back.len(), 1);
+    assert_eq!(back.get(&uuids[0]), Some(&0));
+}
+
+#[test]
+fn fingerprint() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let set: UuidSet = uuids.into_iter().collect();
+    let reversed: UuidSet = uuids.into_iter().rev().collect();
+    let partial: UuidSet = uuids[..2].iter().copied().collect();
+
+    assert_eq!(set.fingerprint(), reversed.fingerprint());
+    assert_ne!(set.fingerprint(), partial.fingerprint());
+    assert_eq!(UuidSet::new().fingerprint(), 0);
+}
+
+#[test]
+fn estimate_difference() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let set = UuidSet::from(uuids);
+    let peer = UuidSet::from([uuids[2], uuids[1], uuids[0]]);
+    assert!(!set.estimate_difference(peer.fingerprint()));
+
+    let peer = UuidSet::from([uuids[0], uuids[1]]);
+    assert!(set.estimate_difference(peer.fingerprint()));
+    assert!(set.estimate_difference(UuidSet::new().fingerprint()));
+}
+
+#[test]
+fn v6() {
+    let node = [1, 2, 3, 4, 5, 6];
+    let uuids: [_; 64] = array::from_fn(|_| Uuid::now_v6(&node));
+    assert!(uuids.iter().all(UuidHasher::supports));
+
+    let mut map: UuidMap<_> = uuids.iter().copied().zip(0..).collect();
+    assert_eq!(map.len(), 64);
+    assert!(uuids.iter().zip(0..).all(|(uuid, idx)| map[uuid] == idx));
+
+    assert_eq!(map.remove(&uuids[0]), Some(0));
+    assert!(!map.contains_key(&uuids[0]));
+    assert_eq!(map.len(), 63);
+
+    // All the UUIDs share the same node, so only their timestamp tells them apart.
+    let hashes: HashSet<_> = uuids
+        .iter()
+        .map(|uuid| UuidBuildHasher.hash_one(uuid))
+        .collect();
+    assert!(hashes.len() > 1);
+
+    // UUIDv4s, UUIDv6s and UUIDv7s are hashed using their bits, with or without
+    // `gxhash-3`.
+    for uuid in [Uuid::new_v4(), uuids[0], Uuid::now_v7()] {
+        let hash = u64::from_be_bytes(uuid.hash_bytes());
+        assert_eq!(UuidBuildHasher.hash_one(uuid), hash);
+    }
+}
+
+#[test]
+fn supports() {
+    assert!(UuidHasher::supports(&Uuid::new_v4()));
+    assert!(UuidHasher::supports(&Uuid::now_v7()));
+
+    // A UUIDv3 with an invalid variant, which only `gxhash-3` can hash.
+    let uuid = Uuid::from_u128(0x0190_0000_0000_3000_0000_0000_0000_0000);
+    assert_eq!(UuidHasher::supports(&uuid), cfg!(feature = "gxhash-3"));
+}
+
+#[test]
+fn composite_keys() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut edges = HashMap::with_hasher(UuidBuildHasher);
+    edges.insert((uuids[0], uuids[1]), 0);
+    edges.insert((uuids[1], uuids[0]), 1);
+    edges.insert((uuids[1], uuids[2]), 2);
+    assert_eq!(edges.len(), 3);
+    assert_eq!(edges[&(uuids[1], uuids[0])], 1);
+    assert!(!edges.contains_key(&(uuids[0], uuids[2])));
+
+    let hash = |key| UuidBuildHasher.hash_one(key);
+    assert_ne!(hash((uuids[0], uuids[1])), hash((uuids[1], uuids[0])));
+
+    let mut triples = HashMap::with_hasher(UuidBuildHasher);
+    triples.insert(uuids, 0);
+    triples.insert([uuids[2], uuids[1], uuids[0]], 1);
+    assert_eq!(triples.len(), 2);
+    assert_eq!(triples[&uuids], 0);
+
+    // The hash of a single UUID isn't mixed.
+    let uuid = Uuid::now_v7();
+    assert_eq!(
+        UuidBuildHasher.hash_one(uuid),
+        u64::from_be_bytes(uuid.hash_bytes())
+    );
+}
+
+#[test]
+#[should_panic]
+fn usize_key() {
+    UuidBuildHasher.hash_one(5usize);
+}
+
+#[test]
+#[should_panic]
+fn uuid_usize_key() {
+    UuidBuildHasher.hash_one((Uuid::new_v4(), 5usize));
+}
+
+#[test]
+fn folding_hasher() {
+    let uuids: [_; 256] = array::from_fn(|_| Uuid::new_v4());
+    let set: FoldingUuidSet = uuids.iter().copied().collect();
+    assert_eq!(set.len(), 256);
+    assert!(uuids.iter().all(|uuid| set.contains(uuid)));
+
+    // UUIDv7s which only differ by their timestamp.
+    let bytes = *Uuid::now_v7().as_bytes();
+    let colliding: Vec<_> = (0..=255)
+        .map(|idx| {
+            let mut bytes = bytes;
+            bytes[5] = idx;
+            Uuid::from_bytes(bytes)
+        })
+        .collect();
+
+    let collisions = |hashes: HashSet<u64>| colliding.len() - hashes.len();
+    let default = colliding.iter().map(|uuid| UuidBuildHasher.hash_one(uuid));
+    let folding = colliding
+        .iter()
+        .map(|uuid| FoldingUuidBuildHasher.hash_one(uuid));
+
+    assert_eq!(collisions(default.collect()), 255);
+    assert_eq!(collisions(folding.collect()), 0);
+}
+
+#[test]
+fn uuid_or_hash() {
+    let v1 = Uuid::now_v1(&[1, 2, 3, 4, 5, 6]);
+    let uuids = [v1, Uuid::new_v4(), Uuid::now_v7(), Uuid::nil(), Uuid::max()];
+
+    let mut map: UuidOrHashMap<_> = uuids.iter().copied().zip(0..).collect();
+    assert_eq!(map.len(), 5);
+    assert!(uuids.iter().zip(0..).all(|(uuid, idx)| map[uuid] == idx));
+    assert_eq!(map.remove(&v1), Some(0));
+
+    let set: UuidOrHashSet = uuids.into_iter().collect();
+    assert_eq!(set.len(), 5);
+
+    // Only differs by bits which aren't used by the fast path.
+    let mut other = *v1.as_bytes();
+    other[0] ^= 0xff;
+    let other = Uuid::from_bytes(other);
+
+    let hasher = UuidOrHashBuildHasher::<DefaultHasher>::default();
+    assert_ne!(hasher.hash_one(v1), hasher.hash_one(other));
+
+    let v7 = Uuid::now_v7();
+    assert_eq!(hasher.hash_one(v7), UuidBuildHasher.hash_one(v7));
+}
+
+#[test]
+fn entropy_collisions() {
+    let mut colliding = *Uuid::now_v7().as_bytes();
+    let original = Uuid::from_bytes(colliding);
+    colliding[0] ^= 0xff;
+    let colliding = Uuid::from_bytes(colliding);
+
+    let mut map = UuidMap::new();
+    map.insert(original, 0);
+    map.insert(Uuid::now_v7(), 1);
+    assert!(!map.has_entropy_collisions());
+
+    let Rehashed::Unchanged(mut map) = map.rehash_colliding_with_fallback() else {
+        panic!("expected the map to be unchanged");
+    };
+    map.insert(colliding, 2);
+    assert!(map.has_entropy_collisions());
+
+    let Rehashed::Fallback(rehashed) = map.rehash_colliding_with_fallback() else {
+        panic!("expected the map to be rehashed");
+    };
+    assert_eq!(rehashed.len(), 3);
+    assert_eq!(rehashed.get(&original), Some(&0));
+    assert_eq!(rehashed.get(&colliding), Some(&2));
+}
+
+#[test]
+fn hash_flood() {
+    let bytes = *Uuid::now_v7().as_bytes();
+
+    let mut map: UuidMap<_> = (0..4).map(|idx| (Uuid::now_v7(), idx)).collect();
+    assert!(!map.detect_hash_flood(1));
+
+    // Only the timestamp differs, which isn't used in the hash.
+    for idx in 0..8 {
+        let mut colliding = bytes;
+        colliding[0] = idx;
+        map.insert(Uuid::from_bytes(colliding), 0);
+    }
+
+    assert!(map.detect_hash_flood(7));
+    assert!(!map.detect_hash_flood(8));
+}
+
+#[test]
+fn v7_window_aggregator() {
+    let at = |secs| Uuid::new_v7(Timestamp::from_unix(NoContext, secs, 0));
+    let uuids = [at(1_000), at(1_030), at(1_060), at(1_090)];
+
+    let mut window = V7WindowAggregator::new(Duration::from_secs(60));
+    assert_eq!(window.insert(uuids[0], 0), None);
+    assert_eq!(window.insert(uuids[1], 1), None);
+    assert_eq!(window.insert(uuids[2], 2), None);
+    assert_eq!(window.len(), 3);
+
+    assert_eq!(window.insert(uuids[3], 3), None);
+    assert_eq!(window.len(), 3);
+    assert!(!window.contains_key(&uuids[0]));
+
+    // Too old to be kept.
+    window.insert(at(1_000), 0);
+    assert_eq!(window.len(), 3);
+
+    assert_eq!(window.insert(uuids[3], 4), Some(3));
+    assert_eq!(window.get(&uuids[3]), Some(&4));
+}
+
+#[test]
+fn multi_map() {
+    let tagged = Uuid::now_v7();
+    let other = Uuid::now_v7();
+    let tags: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut map: UuidMultiMap<_> = tags.iter().map(|tag| (tagged, *tag)).collect();
+    map.insert(other, tags[0]);
+    map.insert(tagged, tags[0]);
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.len_values(), 5);
+    assert_eq!(map.get_all(&tagged), [tags[0], tags[1], tags[2], tags[0]]);
+    assert_eq!(map.get_all(&other), [tags[0]]);
+    assert!(map.get_all(&Uuid::new_v4()).is_empty());
+
+    assert!(map.remove_value(&tagged, &tags[0]));
+    assert_eq!(map.get_all(&tagged), [tags[1], tags[2], tags[0]]);
+    assert!(!map.remove_value(&tagged, &Uuid::new_v4()));
+
+    assert!(map.remove_value(&other, &tags[0]));
+    assert!(!map.contains_key(&other));
+    assert_eq!(map.len_values(), 3);
+}
+
+#[test]
+fn bimap() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let mut map = UuidBiMap::new();
+
+    assert_eq!(map.insert(uuids[0], "x"), (None, None));
+    assert_eq!(map.insert(uuids[1], "y"), (None, None));
+    assert_eq!(map.get_by_uuid(&uuids[1]), Some(&"y"));
+    assert_eq!(map.get_by_value(&"x"), Some(&uuids[0]));
+
+    // Collides on both sides, evicting both entries.
+    assert_eq!(map.insert(uuids[0], "y"), (Some("x"), Some(uuids[1])));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get_by_value(&"x"), None);
+    assert_eq!(map.get_by_uuid(&uuids[1]), None);
+
+    // Collides on the value side only.
+    assert_eq!(map.insert(uuids[2], "y"), (None, Some(uuids[0])));
+    assert_eq!(map.get_by_value(&"y"), Some(&uuids[2]));
+    assert!(!map.contains_key(&uuids[0]));
+
+    // Re-inserting the same entry doesn't report its own UUID as evicted.
+    assert_eq!(map.insert(uuids[2], "y"), (Some("y"), None));
+
+    assert_eq!(map.remove_by_value(&"y"), Some(uuids[2]));
+    assert!(map.is_empty());
+    map.insert(uuids[1], "z");
+    assert_eq!(map.remove_by_uuid(&uuids[1]), Some("z"));
+    assert_eq!(map.get_by_value(&"z"), None);
+}
+
+#[test]
+fn interned_map() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let mut map = InternedUuidMap::new();
+
+    map.insert(uuids[0], String::from("config"));
+    map.insert(uuids[1], String::from("config"));
+    map.insert(uuids[2], String::from("other"));
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.distinct_values(), 2);
+    assert!(Arc::ptr_eq(&map[&uuids[0]], &map[&uuids[1]]));
+
+    assert_eq!(
+        map.remove(&uuids[2]).as_deref(),
+        Some(&String::from("other"))
+    );
+    assert_eq!(map.distinct_values(), 1);
+
+    let previous = map.insert(uuids[0], String::from("updated")).unwrap();
+    assert_eq!(*previous, "config");
+    assert_eq!(map.distinct_values(), 2);
+
+    map.remove(&uuids[1]);
+    assert_eq!(map.distinct_values(), 1);
+}
+
+#[test]
+fn btree_map() {
+    let map: UuidMap<_> = (0..10).map(|idx| (Uuid::new_v4(), idx)).collect();
+
+    let btree_map = map.to_btree_map();
+    assert_eq!(btree_map.len(), map.len());
+    assert!(btree_map.keys().is_sorted());
+    assert!(
+        btree_map
+            .iter()
+            .all(|(uuid, value)| map.get(uuid) == Some(value))
+    );
+
+    assert_eq!(map.into_btree_map(), btree_map);
+}
+
+#[test]
+fn into_index_map_sorted_by_value() {
+    let mut uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+    uuids[1..3].sort();
+
+    let map: UuidMap<_> = uuids.into_iter().zip([30, 10, 10, 20]).collect();
+    let sorted = map.into_index_map_sorted_by_value();
+
+    assert!(sorted.values().eq(&[10, 10, 20, 30]));
+    assert!(sorted.keys().eq(&[uuids[1], uuids[2], uuids[3], uuids[0]]));
+}
+
+#[test]
+fn densest_bucket() {
+    let bytes = *Uuid::now_v7().as_bytes();
+
+    let mut map: UuidMap<_> = (0..4).map(|idx| (Uuid::now_v7(), idx)).collect();
+    assert_eq!(map.densest_bucket().len(), 1);
+
+    // Only the timestamp differs, which isn't used in the hash.
+    let colliding: Vec<_> = (0..3)
+        .map(|idx| {
+            let mut colliding = bytes;
+            colliding[0] = idx;
+            Uuid::from_bytes(colliding)
+        })
+        .collect();
+    map.extend(colliding.iter().map(|uuid| (*uuid, 0)));
+
+    let mut bucket = map.densest_bucket();
+    bucket.sort();
+    assert!(bucket.into_iter().eq(&colliding));
+
+    assert!(UuidMap::<u32>::new().densest_bucket().is_empty());
+}
+
+#[test]
+fn count_newer_than() {
+    let at = |secs| Uuid::new_v7(Timestamp::from_unix(NoContext, secs, 0));
+
+    let mut map: UuidMap<_> = [at(1_000), at(1_010), at(1_020), at(1_030)]
+        .into_iter()
+        .zip(0..)
+        .collect();
+    map.insert(Uuid::new_v4(), 4);
+
+    let cutoff = UNIX_EPOCH + Duration::from_secs(1_015);
+    assert_eq!(map.count_newer_than(cutoff), 2);
+    assert_eq!(map.count_newer_than(UNIX_EPOCH), 4);
+    assert_eq!(map.len(), 5);
+
+    assert_eq!(map.split_off_after(cutoff).len(), 2);
+}
+
+#[test]
+fn nearest_by_time() {
+    let at = |secs| Uuid::new_v7(Timestamp::from_unix(NoContext, secs, 0));
+    let uuids = [at(1_000), at(1_010), at(1_030)];
+
+    let mut map: UuidMap<_> = uuids.iter().copied().zip(0..).collect();
+    map.insert(Uuid::new_v4(), 3);
+
+    let target = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+    assert_eq!(map.nearest_by_time(target(1_004)), Some(&uuids[0]));
+    assert_eq!(map.nearest_by_time(target(1_012)), Some(&uuids[1]));
+    assert_eq!(map.nearest_by_time(target(1_025)), Some(&uuids[2]));
+    assert_eq!(map.nearest_by_time(target(0)), Some(&uuids[0]));
+
+    assert_eq!(UuidMap::<u32>::new().nearest_by_time(target(0)), None);
+}
+
+#[test]
+fn demux() {
+    #[derive(PartialEq, Debug)]
+    enum Event {
+        Created,
+        Updated(u32),
+        Deleted,
+    }
+
+    let map: UuidMap<_> = [
+        Event::Created,
+        Event::Updated(1),
+        Event::Updated(2),
+        Event::Deleted,
+    ]
+    .into_iter()
+    .map(|event| (Uuid::new_v4(), event))
+    .collect();
+
+    let maps = map.demux(mem::discriminant);
+    assert_eq!(maps.len(), 3);
+    assert_eq!(maps.values().map(|map| map.len()).sum::<usize>(), 4);
+    assert_eq!(maps[&mem::discriminant(&Event::Updated(0))].len(), 2);
+}
+
+#[test]
+fn diff() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+    let old = UuidMap::from([(uuids[0], 0), (uuids[1], 1), (uuids[2], 2)]);
+    let new = UuidMap::from([(uuids[1], 1), (uuids[2], 3), (uuids[3], 4)]);
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.added, UuidMap::from([(uuids[3], 4)]));
+    assert_eq!(diff.removed, UuidMap::from([(uuids[0], 0)]));
+    assert_eq!(diff.changed, UuidMap::from([(uuids[2], (2, 3))]));
+
+    assert!(old.diff(&old).is_empty());
+}
+
+#[test]
+fn apply_diff() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+    let mut a = UuidMap::from([(uuids[0], 0), (uuids[1], 1), (uuids[2], 2)]);
+    let b = UuidMap::from([(uuids[1], 1), (uuids[2], 3), (uuids[3], 4)]);
+
+    let diff = a.diff(&b);
+    a.apply_diff(&diff);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn invert() {
+    let uuids: [_; 5] = array::from_fn(|_| Uuid::new_v4());
+    let map: UuidMap<_> = uuids
+        .iter()
+        .copied()
+        .zip(["a", "b", "a", "c", "b"])
+        .collect();
+
+    let inverted = map.invert();
+    assert_eq!(inverted.len(), 3);
+    assert_eq!(inverted["a"], UuidSet::from_iter([uuids[0], uuids[2]]));
+    assert_eq!(inverted["b"], UuidSet::from_iter([uuids[1], uuids[4]]));
+    assert_eq!(inverted["c"], UuidSet::from_iter([uuids[3]]));
+}
+
+#[test]
+fn inner() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let map: UuidMap<_> = uuids.iter().copied().zip(0..).collect();
+    let inner: &HashMap<_, _, UuidBuildHasher> = map.as_inner();
+    assert_eq!(inner.len(), 3);
+    assert_eq!(map.clone().into_inner(), HashMap::from(map));
+
+    let map: UuidIndexMap<_> = uuids.iter().copied().zip(0..).collect();
+    assert!(map.as_inner().keys().eq(&uuids));
+    assert!(map.into_inner().into_keys().eq(uuids));
+
+    let set: UuidSet = uuids.into_iter().collect();
+    assert!(uuids.iter().all(|uuid| set.as_inner().contains(uuid)));
+    assert_eq!(set.into_inner().len(), 3);
+
+    let set: UuidIndexSet = uuids.into_iter().collect();
+    assert!(set.as_inner().iter().eq(&uuids));
+    assert!(set.into_inner().into_iter().eq(uuids));
+}
+
+#[test]
+fn from_arrays() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let map = UuidMap::from([(uuids[0], 0), (uuids[1], 1), (uuids[2], 2)]);
+    assert_eq!(map.len(), 3);
+    assert_eq!(map[&uuids[1]], 1);
+
+    let map = UuidIndexMap::from([(uuids[2], 2), (uuids[0], 0)]);
+    assert!(map.keys().eq(&[uuids[2], uuids[0]]));
+
+    let set = UuidSet::from(uuids);
+    assert!(uuids.iter().all(|uuid| set.contains(uuid)));
+
+    let set = UuidIndexSet::from(uuids);
+    assert!(set.iter().eq(&uuids));
+}
+
+#[test]
+fn keys_blocks() {
+    let set: UuidSet = (0..16).map(|_| Uuid::new_v4()).collect();
+
+    let blocks = set.keys_blocks();
+    assert_eq!(blocks.len(), 16);
+    assert_eq!(blocks.as_ptr() as usize % 16, 0);
+    assert_eq!(mem::size_of_val(blocks.as_slice()), 16 * 16);
+    assert!(
+        blocks
+            .iter()
+            .all(|block| set.contains(&Uuid::from_bytes(**block)))
+    );
+}
+
+#[test]
+fn hash_collections() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let [forward, backward] = [uuids, [uuids[2], uuids[1], uuids[0]]];
+    fn hash(value: impl Hash) -> u64 {
+        BuildHasherDefault::<DefaultHasher>::default().hash_one(value)
+    }
+
+    let a = UuidSet::from(forward);
+    let b = UuidSet::from(backward);
+    assert_eq!(hash(&a), hash(&b));
+    assert_ne!(hash(&a), hash(UuidSet::new()));
+
+    let mut outer = HashMap::new();
+    outer.insert(a, 0);
+    assert_eq!(outer.get(&b), Some(&0));
+
+    let a = UuidIndexSet::from(forward);
+    let b = UuidIndexSet::from(backward);
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+
+    let a = UuidMap::from(forward.map(|uuid| (uuid, 0)));
+    let b = UuidMap::from(backward.map(|uuid| (uuid, 0)));
+    let c = UuidMap::from(backward.map(|uuid| (uuid, 1)));
+    assert_eq!(hash(&a), hash(&b));
+    assert_ne!(hash(&a), hash(&c));
+
+    let a = UuidIndexMap::from(forward.map(|uuid| (uuid, 0)));
+    let b = UuidIndexMap::from(backward.map(|uuid| (uuid, 0)));
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[test]
+fn set_ops() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+    let a = UuidSet::from([uuids[0], uuids[1], uuids[2]]);
+    let b = UuidSet::from([uuids[1], uuids[2], uuids[3]]);
+
+    assert_eq!(&a | &b, UuidSet::from(uuids));
+    assert_eq!(&a & &b, UuidSet::from([uuids[1], uuids[2]]));
+    assert_eq!(&a ^ &b, UuidSet::from([uuids[0], uuids[3]]));
+    assert_eq!(&a - &b, UuidSet::from([uuids[0]]));
+
+    let a = UuidIndexSet::from([uuids[0], uuids[1], uuids[2]]);
+    let b = UuidIndexSet::from([uuids[3], uuids[2], uuids[1]]);
+
+    assert!((&a | &b).iter().eq(&uuids));
+    assert!((&a & &b).iter().eq(&uuids[1..3]));
+    assert!((&a ^ &b).iter().eq(&[uuids[0], uuids[3]]));
+    assert!((&a - &b).iter().eq(&uuids[..1]));
+}
+
+#[test]
+fn key_set() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let map: UuidMap<_> = uuids.iter().copied().zip(0..).collect();
+    let keys = map.key_set();
+    assert_eq!(keys.len(), 3);
+    assert!(map.keys().all(|uuid| keys.contains(uuid)));
+
+    let map: UuidIndexMap<_> = uuids.iter().copied().zip(0..).collect();
+    assert!(map.key_set().iter().eq(&uuids));
+}
+
+#[test]
+fn borrowed_into_iter() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut map = UuidMap::from(uuids.map(|uuid| (uuid, 1)));
+    for (_, value) in &mut map {
+        *value += 1;
+    }
+    assert_eq!((&map).into_iter().map(|(_, value)| value).sum::<i32>(), 6);
+
+    let mut map = UuidIndexMap::from(uuids.map(|uuid| (uuid, 1)));
+    for (_, value) in &mut map {
+        *value *= 3;
+    }
+    assert!((&map).into_iter().map(|(uuid, _)| uuid).eq(&uuids));
+    assert!(map.values().all(|value| *value == 3));
+
+    let set = UuidSet::from(uuids);
+    let mut visited = 0;
+    for uuid in &set {
+        assert!(uuids.contains(uuid));
+        visited += 1;
+    }
+    assert_eq!(visited, 3);
+
+    let set = UuidIndexSet::from(uuids);
+    assert!((&set).into_iter().eq(&uuids));
+}
+
+#[test]
+fn range_by_time() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut v7s: Vec<_> = UuidSet::sequential_v7(start, 8, Duration::from_millis(1))
+        .into_iter()
+        .collect();
+    v7s.sort();
+
+    let mut set = UuidSet::from_iter(v7s.iter().copied());
+    set.insert(Uuid::new_v4());
+    let map: UuidMap<_> = set.iter().map(|uuid| (*uuid, ())).collect();
+
+    let (from, to) = (
+        start + Duration::from_millis(2),
+        start + Duration::from_millis(5),
+    );
+    let expected = UuidSet::from_iter(v7s[2..5].iter().copied());
+
+    assert_eq!(
+        set.range_by_time(from, to).copied().collect::<UuidSet>(),
+        expected
+    );
+    let keys: UuidSet = map.range_by_time(from, to).map(|(uuid, _)| *uuid).collect();
+    assert_eq!(keys, expected);
+
+    assert_eq!(set.range_by_time(to, from).count(), 0);
+}
+
+#[test]
+fn iter_chronological() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut v7s: Vec<_> = UuidSet::sequential_v7(start, 8, Duration::from_millis(1))
+        .into_iter()
+        .collect();
+    v7s.extend(UuidSet::sequential_v7(start, 2, Duration::ZERO));
+    v7s.reverse();
+
+    let mut map: UuidMap<_> = v7s.iter().map(|uuid| (*uuid, ())).collect();
+    map.insert(Uuid::new_v4(), ());
+
+    let times: Vec<_> = map
+        .iter_chronological()
+        .map(|(uuid, _)| v7_time(uuid).unwrap())
+        .collect();
+
+    assert_eq!(times.len(), 9);
+    assert!(times.is_sorted());
+    assert_eq!(times[0], times[1]);
+}
+
+#[test]
+fn retain_newest_n() {
+    let v7s = UuidSet::sequential_v7(SystemTime::now(), 10, Duration::from_secs(1));
+    let v4 = Uuid::new_v4();
+
+    let mut map: UuidMap<_> = v7s.iter().map(|uuid| (*uuid, ())).collect();
+    map.insert(v4, ());
+
+    map.retain_newest_n(3);
+    assert_eq!(map.len(), 4);
+    assert!(map.contains_key(&v4));
+
+    let mut sorted: Vec<_> = v7s.iter().collect();
+    sorted.sort();
+    assert!(sorted[7..].iter().all(|uuid| map.contains_key(uuid)));
+
+    map.retain_newest_n(0);
+    assert_eq!(map.keys().collect::<Vec<_>>(), [&v4]);
+}
+
+#[test]
+fn take() {
+    let mut map = UuidMap::with_capacity(64);
+    map.extend((0..4).map(|idx| (Uuid::new_v4(), idx)));
+    let capacity = map.capacity();
+
+    let taken = map.take();
+    assert_eq!(taken.len(), 4);
+    assert_eq!(taken.capacity(), capacity);
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), 0);
+}
+
+#[test]
+fn keys_where() {
+    struct User {
+        active: bool,
+    }
+
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+    let map: UuidMap<_> = uuids
+        .iter()
+        .zip([true, false, true, false])
+        .map(|(uuid, active)| (*uuid, User { active }))
+        .collect();
+
+    let active = map.keys_where(|user| user.active);
+    assert_eq!(active, UuidSet::from_iter([uuids[0], uuids[2]]));
+    assert!(map.keys_where(|_| false).is_empty());
+}
+
+#[test]
+fn unique_valued_keys() {
+    let uuids: [_; 5] = array::from_fn(|_| Uuid::new_v4());
+    let map: UuidMap<_> = uuids
+        .iter()
+        .copied()
+        .zip(["a", "b", "a", "c", "b"])
+        .collect();
+
+    let unique = map.unique_valued_keys();
+    assert_eq!(unique, UuidSet::from_iter([uuids[3]]));
+
+    assert!(UuidMap::<()>::new().unique_valued_keys().is_empty());
+}
+
+#[test]
+fn into_chunks() {
+    let map: UuidMap<_> = (0..10).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let chunks = map.clone().into_chunks(4);
+
+    assert!(chunks.iter().map(|chunk| chunk.len()).eq([4, 4, 2]));
+    assert_eq!(chunks.into_iter().flatten().collect::<UuidMap<_>>(), map);
+    assert!(UuidMap::<u32>::new().into_chunks(4).is_empty());
+
+    let map: UuidIndexMap<_> = (0..10).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let chunks = map.into_chunks(3);
+
+    assert!(chunks.iter().map(|chunk| chunk.len()).eq([3, 3, 3, 1]));
+    assert!(
+        chunks
+            .iter()
+            .flat_map(|chunk| chunk.values())
+            .eq(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9])
+    );
+}
+
+#[test]
+fn try_extend() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let mut map = UuidMap::new();
+
+    let err = map
+        .try_extend([(uuids[0], 0), (UNSUPPORTED, 1), (uuids[1], 2)])
+        .unwrap_err();
+    assert_eq!(err, UnsupportedVersionError(UNSUPPORTED));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&uuids[0]), Some(&0));
+
+    assert!(map.try_extend([(uuids[1], 1), (uuids[2], 2)]).is_ok());
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+fn try_insert() {
+    let uuid = Uuid::new_v4();
+
+    let mut map = UuidMap::new();
+    *map.try_insert(uuid, 0).unwrap() += 1;
+    assert_eq!(map.try_insert(uuid, 2).copied(), Ok(2));
+    assert_eq!(
+        map.try_insert(UNSUPPORTED, 3),
+        Err(UnsupportedVersionError(UNSUPPORTED))
+    );
+    assert_eq!(map.len(), 1);
+
+    let mut set = UuidSet::new();
+    assert_eq!(set.try_insert(uuid), Ok(true));
+    assert_eq!(set.try_insert(uuid), Ok(false));
+    assert_eq!(
+        set.try_insert(UNSUPPORTED),
+        Err(UnsupportedVersionError(UNSUPPORTED))
+    );
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+fn from_keys() {
+    let keys: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let map = UuidMap::from_keys(keys, |uuid| uuid.as_bytes()[15]).unwrap();
+
+    assert_eq!(map.len(), 3);
+    assert!(keys.iter().all(|uuid| map[uuid] == uuid.as_bytes()[15]));
+
+    let err = UuidMap::from_keys([keys[0], UNSUPPORTED], |_| 0).unwrap_err();
+    assert_eq!(err, UnsupportedVersionError(UNSUPPORTED));
+}
+
+#[test]
+fn replace_all() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let mut map = UuidMap::from_iter([(uuids[0], 0)]);
+
+    let err = map
+        .replace_all([(uuids[1], 1), (UNSUPPORTED, 2)])
+        .unwrap_err();
+    assert_eq!(err, UnsupportedVersionError(UNSUPPORTED));
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&uuids[0]), Some(&0));
+
+    assert!(map.replace_all([(uuids[1], 1), (uuids[2], 2)]).is_ok());
+    assert_eq!(map.len(), 2);
+    assert!(!map.contains_key(&uuids[0]));
+    assert_eq!(map.get(&uuids[2]), Some(&2));
+}
+
+#[test]
+fn is_v7_time_sorted() {
+    let v7s = UuidSet::sequential_v7(SystemTime::now(), 4, Duration::from_secs(1));
+    let mut sorted: Vec<_> = v7s.into_iter().collect();
+    sorted.sort();
+
+    let mut map: UuidIndexMap<_> = sorted.iter().map(|uuid| (*uuid, ())).collect();
+    map.insert_before(2, Uuid::new_v4(), ());
+    assert!(map.is_v7_time_sorted());
+
+    map.swap_indices(0, 4);
+    assert!(!map.is_v7_time_sorted());
+}
+
+#[test]
+fn index_map_order() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut map: UuidIndexMap<_> = uuids.iter().copied().zip(0..).collect();
+
+    assert!(map.keys().eq(&uuids));
+    assert!(map.values().eq(&[0, 1, 2, 3]));
+    assert!(map.iter().map(|(uuid, _)| uuid).eq(&uuids));
+
+    for (_, value) in map.iter_mut() {
+        *value *= 10;
+    }
+
+    assert!(map.values().eq(&[0, 10, 20, 30]));
+
+    map.shift_remove(&uuids[1]);
+    map.insert(uuids[1], 1);
+    assert!(map.keys().eq(&[uuids[0], uuids[2], uuids[3], uuids[1]]));
+
+    map.swap_remove(&uuids[0]);
+    map.insert(uuids[0], 0);
+    assert!(map.keys().eq(&[uuids[1], uuids[2], uuids[3], uuids[0]]));
+
+    let entries: Vec<_> = map.into_iter().collect();
+    assert_eq!(
+        entries,
+        [(uuids[1], 1), (uuids[2], 20), (uuids[3], 30), (uuids[0], 0)],
+    );
+}
+
+#[test]
+fn index_set_order() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::now_v7());
+
+    let mut set: UuidIndexSet = uuids.iter().copied().collect();
+    assert!(set.iter().eq(&uuids));
+
+    set.shift_remove(&uuids[1]);
+    set.insert(uuids[1]);
+    assert!(set.iter().eq(&[uuids[0], uuids[2], uuids[3], uuids[1]]));
+
+    set.swap_remove(&uuids[0]);
+    set.insert(uuids[0]);
+    assert!(set.iter().eq(&[uuids[1], uuids[2], uuids[3], uuids[0]]));
+
+    let entries: Vec<_> = set.into_iter().collect();
+    assert_eq!(entries, [uuids[1], uuids[2], uuids[3], uuids[0]]);
+}
+
+#[test]
+fn partition_keys() {
+    let map: UuidMap<_> = (0..32).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let tagged = |uuid: &Uuid| uuid.as_bytes()[0] & 1 == 1;
+
+    let (matching, others) = map.clone().partition_keys(tagged);
+
+    assert_eq!(matching.len() + others.len(), map.len());
+    assert!(matching.keys().all(tagged));
+    assert!(!others.keys().any(tagged));
+
+    for (uuid, value) in matching.iter().chain(others.iter()) {
+        assert_eq!(map.get(uuid), Some(value));
+    }
+}
+
+#[test]
+fn remove_all() {
+    let uuids: [_; 5] = array::from_fn(|_| Uuid::new_v4());
+    let keys: UuidIndexSet = [uuids[3], uuids[1]].into_iter().collect();
+
+    let mut map: UuidIndexMap<_> = uuids.iter().copied().zip(0..).collect();
+    let removed = map.remove_all_shift(&keys);
+
+    assert!(map.keys().eq(&[uuids[0], uuids[2], uuids[4]]));
+    assert!(removed.iter().eq([(&uuids[1], &1), (&uuids[3], &3)]));
+
+    let mut map: UuidIndexMap<_> = uuids.iter().copied().zip(0..).collect();
+    let removed = map.remove_all_swap(&keys);
+
+    assert!(map.keys().eq(&[uuids[0], uuids[4], uuids[2]]));
+    assert!(removed.iter().eq([(&uuids[3], &3), (&uuids[1], &1)]));
+}
+
+#[test]
+fn values_by_frequency() {
+    let values = ["a", "b", "c", "c", "c", "a", "c"];
+    let map: UuidMap<_> = values.into_iter().map(|v| (Uuid::new_v4(), v)).collect();
+
+    let frequencies = map.values_by_frequency();
+    assert_eq!(frequencies, [(&"c", 4), (&"a", 2), (&"b", 1)]);
+}
+
+#[test]
+fn project() {
+    let map: UuidMap<_> = (0..1_000).map(|idx| (Uuid::new_v4(), idx)).collect();
+
+    let mut allow: UuidSet = map.keys().take(3).copied().collect();
+    allow.insert(Uuid::new_v4());
+
+    let projected = map.project(&allow);
+
+    assert_eq!(projected.len(), 3);
+    for (uuid, value) in projected.iter() {
+        assert!(allow.contains(uuid));
+        assert_eq!(map.get(uuid), Some(value));
+    }
+}
+
+#[test]
+fn group_by_millisecond() {
+    let burst: [_; 3] =
+        array::from_fn(|_| Uuid::new_v7(Timestamp::from_unix(NoContext, 1_700_000_000, 0)));
+    let other = Uuid::new_v7(Timestamp::from_unix(NoContext, 1_700_000_001, 0));
+
+    let set: UuidSet = burst.into_iter().chain([other, Uuid::new_v4()]).collect();
+
+    let groups = set.group_by_millisecond();
+
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[&1_700_000_000_000].len(), 3);
+    assert!(
+        burst
+            .iter()
+            .all(|uuid| groups[&1_700_000_000_000].contains(uuid))
+    );
+    assert_eq!(groups[&1_700_000_001_000], [other]);
+}
+
+#[cfg(feature = "rkyv-08")]
+#[test]
+fn archived_set_contains() {
+    use rkyv::{Archive, rancor};
+
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let set: UuidSet = uuids[..2].iter().copied().collect();
+
+    let bytes = rkyv::to_bytes::<rancor::Error>(&set).unwrap();
+    // SAFETY: `bytes` was just produced by serializing a `UuidSet`.
+    let archived = unsafe { rkyv::access_unchecked::<<UuidSet as Archive>::Archived>(&bytes) };
+
+    assert!(archived.contains(&uuids[0]));
+    assert!(archived.contains(&uuids[1]));
+    assert!(!archived.contains(&uuids[2]));
+    assert!(!archived.contains(&Uuid::nil()));
+}
+
+#[test]
+fn lazy_map() {
+    let uuids: [_; 2] = array::from_fn(|_| Uuid::new_v4());
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+
+    let mut map = LazyUuidMap::with_eviction_hook({
+        let evicted = evicted.clone();
+        move |uuid, value| evicted.lock().unwrap().push((uuid, value))
+    });
+
+    let mut calls = 0;
+    for _ in 0..3 {
+        for (idx, uuid) in uuids.iter().enumerate() {
+            let value = map.get_or_compute(*uuid, || {
+                calls += 1;
+                idx
+            });
+
+            assert_eq!(*value, idx);
+        }
+    }
+
+    assert_eq!(calls, 2);
+    assert_eq!(map.len(), 2);
+
+    assert!(map.evict(&uuids[0]));
+    assert!(!map.evict(&uuids[0]));
+    assert_eq!(*evicted.lock().unwrap(), [(uuids[0], 0)]);
+
+    map.get_or_compute(uuids[0], || {
+        calls += 1;
+        0
+    });
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn cardinality_estimator() {
+    let mut estimator = UuidCardinalityEstimator::new();
+    assert_eq!(estimator.estimate(), 0);
+
+    let uuids: Vec<_> = (0..50_000).map(|_| Uuid::new_v4()).collect();
+    estimator.extend(uuids.iter().copied());
+    estimator.extend(uuids.iter().copied());
+    estimator.extend((0..50_000).map(|_| Uuid::now_v7()));
+
+    let estimate = estimator.estimate();
+    assert!((97_000..=103_000).contains(&estimate), "{estimate}");
+}
+
+#[cfg(feature = "scylla-1")]
+#[test]
+fn scylla_index_type_check() {
+    use scylla::{
+        cluster::metadata::{CollectionType, ColumnType, NativeType},
+        deserialize::{DeserializationError, FrameSlice, value::DeserializeValue},
+        errors::TypeCheckError,
+        serialize::{
+            SerializationError,
+            value::SerializeValue,
+            writers::{CellWriter, WrittenCellProof},
+        },
+    };
+
+    uuid_like::wrapper!(ScyllaId);
+
+    impl SerializeValue for ScyllaId {
+        fn serialize<'b>(
+            &self,
+            typ: &ColumnType,
+            writer: CellWriter<'b>,
+        ) -> Result<WrittenCellProof<'b>, SerializationError> {
+            self.0.serialize(typ, writer)
+        }
+    }
+
+    impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for ScyllaId {
+        fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+            <Uuid as DeserializeValue>::type_check(typ)
+        }
+
+        fn deserialize(
+            typ: &'metadata ColumnType<'metadata>,
+            v: Option<FrameSlice<'frame>>,
+        ) -> Result<Self, DeserializationError> {
+            Uuid::deserialize(typ, v).map(Self)
+        }
+    }
+
+    let uuid = || Box::new(ColumnType::Native(NativeType::Uuid));
+    let map = ColumnType::Collection {
+        frozen: false,
+        typ: CollectionType::Map(uuid(), Box::new(ColumnType::Native(NativeType::Int))),
+    };
+    let set = ColumnType::Collection {
+        frozen: false,
+        typ: CollectionType::Set(uuid()),
+    };
+
+    assert!(<UuidIndexMap<i32> as DeserializeValue>::type_check(&map).is_ok());
+    assert!(<UuidIndexMap<i32> as DeserializeValue>::type_check(&set).is_err());
+    assert!(<UuidIndexSet as DeserializeValue>::type_check(&set).is_ok());
+    assert!(<UuidIndexSet as DeserializeValue>::type_check(&map).is_err());
+
+    type IdIndexMap = UuidLikeIndexMap<ScyllaId, i32>;
+    type IdIndexSet = UuidLikeIndexSet<ScyllaId>;
+
+    assert!(<IdIndexMap as DeserializeValue>::type_check(&map).is_ok());
+    assert!(<IdIndexMap as DeserializeValue>::type_check(&set).is_err());
+    assert!(<IdIndexSet as DeserializeValue>::type_check(&set).is_ok());
+    assert!(<IdIndexSet as DeserializeValue>::type_check(&map).is_err());
+}
+
+#[cfg(feature = "serde-1")]
+#[test]
+fn serde() {
+    let uuids = [Uuid::new_v4(), Uuid::now_v7()];
+
+    let map: UuidMap<_> = uuids.into_iter().zip(0..).collect();
+    let json = serde_json::to_string(&map).unwrap();
+    assert_eq!(serde_json::from_str::<UuidMap<i32>>(&json).unwrap(), map);
+
+    let set: UuidSet = uuids.into_iter().collect();
+    let json = serde_json::to_string(&set).unwrap();
+    assert_eq!(serde_json::from_str::<UuidSet>(&json).unwrap(), set);
+
+    let json = format!(r#"{{"{}": 0, "{UNSUPPORTED}": 1}}"#, uuids[0]);
+    let err = serde_json::from_str::<UuidMap<i32>>(&json).unwrap_err();
+    assert!(err.to_string().contains(&UNSUPPORTED.to_string()));
+
+    let json = format!(r#"["{}", "{UNSUPPORTED}"]"#, uuids[0]);
+    let err = serde_json::from_str::<UuidSet>(&json).unwrap_err();
+    assert!(err.to_string().contains(&UNSUPPORTED.to_string()));
+}
+
+#[cfg(feature = "serde-1")]
+uuid_like::wrapper! {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    SerdeId
+}
+
+#[cfg(feature = "serde-1")]
+#[test]
+fn serde_index_order() {
+    use uuid_like::UuidLike;
+
+    let uuids: [_; 8] = array::from_fn(|_| Uuid::new_v4());
+
+    let map: UuidIndexMap<_> = uuids.into_iter().zip(0..).collect();
+    let json = serde_json::to_string(&map).unwrap();
+    let map: UuidIndexMap<i32> = serde_json::from_str(&json).unwrap();
+    assert!(map.keys().eq(&uuids));
+
+    let set: UuidIndexSet = uuids.into_iter().rev().collect();
+    let json = serde_json::to_string(&set).unwrap();
+    let set: UuidIndexSet = serde_json::from_str(&json).unwrap();
+    assert!(set.iter().eq(uuids.iter().rev()));
+
+    let keys = uuids.map(|uuid| SerdeId::from_bytes(uuid.into_bytes()));
+
+    let map: UuidLikeIndexMap<_, _> = keys.into_iter().zip(0..).collect();
+    let json = serde_json::to_string(&map).unwrap();
+    let map: UuidLikeIndexMap<SerdeId, i32> = serde_json::from_str(&json).unwrap();
+    assert!(map.keys().eq(&keys));
+}
+
+#[cfg(feature = "serde-1")]
+#[test]
+fn serde_seq() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Contract {
+        #[serde(
+            serialize_with = "super::serialize_as_seq",
+            deserialize_with = "super::deserialize_from_seq"
+        )]
+        parties: UuidIndexMap<i32>,
+    }
+
+    let uuids: [_; 2] = array::from_fn(|_| Uuid::new_v4());
+    let contract = Contract {
+        parties: uuids.into_iter().zip(0..).collect(),
+    };
+
+    let json = serde_json::to_string(&contract).unwrap();
+    assert_eq!(
+        json,
+        format!(r#"{{"parties":[["{}",0],["{}",1]]}}"#, uuids[0], uuids[1]),
+    );
+
+    let contract: Contract = serde_json::from_str(&json).unwrap();
+    assert!(contract.parties.keys().eq(&uuids));
+
+    let json = format!(r#"{{"parties":[["{UNSUPPORTED}",0]]}}"#);
+    assert!(serde_json::from_str::<Contract>(&json).is_err());
+}
+
+#[cfg(feature = "serde-1")]
+#[test]
+fn serde_seq_non_string_keys() {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use uuid_like::UuidLike;
+
+    uuid_like::wrapper!(PairId);
+
+    /// Serializes as a pair of integers, which can't be the key of a JSON object.
+    impl Serialize for PairId {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.as_u64_pair().serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for PairId {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (high, low) = Deserialize::deserialize(deserializer)?;
+            Ok(Self::from_u64_pair(high, low))
+        }
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Contract {
+        #[serde(
+            serialize_with = "super::serialize_as_seq",
+            deserialize_with = "super::deserialize_from_seq"
+        )]
+        parties: UuidLikeMap<PairId, i32>,
+    }
+
+    let keys: [_; 3] = array::from_fn(|_| PairId::from_bytes(Uuid::new_v4().into_bytes()));
+    let parties: UuidLikeMap<_, _> = keys.into_iter().zip(0..).collect();
+    assert!(serde_json::to_string(&parties).is_err());
+
+    let json = serde_json::to_string(&Contract { parties }).unwrap();
+    let contract: Contract = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(contract.parties.len(), 3);
+    assert!(
+        keys.iter()
+            .zip(0..)
+            .all(|(key, idx)| contract.parties[key] == idx)
+    );
+}
+
+#[cfg(feature = "serde-1")]
+#[test]
+fn deny_duplicate_keys() {
+    let uuids: [_; 2] = array::from_fn(|_| Uuid::new_v4());
+
+    let json = format!(r#"{{"{}": 0, "{}": 1}}"#, uuids[0], uuids[1]);
+    let map: DenyDuplicateKeys<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(map.0.len(), 2);
+
+    let json = format!(
+        r#"{{"{}": 0, "{}": 1, "{}": 2}}"#,
+        uuids[0], uuids[1], uuids[0]
+    );
+    let err = serde_json::from_str::<DenyDuplicateKeys<i32>>(&json).unwrap_err();
+    assert!(err.to_string().contains(&uuids[0].to_string()));
+
+    let map: UuidMap<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(map.get(&uuids[0]), Some(&2));
+}
+
+#[test]
+fn canonical_bytes() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::now_v7());
+
+    let set: UuidSet = uuids.into_iter().collect();
+    let reversed: UuidSet = uuids.into_iter().rev().collect();
+
+    let bytes = set.to_canonical_bytes();
+    assert_eq!(bytes.len(), 48);
+    assert_eq!(bytes, reversed.to_canonical_bytes());
+    assert_eq!(UuidSet::from_canonical_bytes(&bytes), Ok(set));
+
+    assert_eq!(
+        UuidSet::from_canonical_bytes(&bytes[1..]),
+        Err(CanonicalBytesError::InvalidLength(47)),
+    );
+
+    let mut unsorted = bytes[16..].to_vec();
+    unsorted.extend_from_slice(&bytes[..16]);
+    assert_eq!(
+        UuidSet::from_canonical_bytes(&unsorted),
+        Err(CanonicalBytesError::Unsorted(uuids[0])),
+    );
+}
+
+#[test]
+fn split_off_after() {
+    let old = Uuid::new_v7(Timestamp::from_unix(NoContext, 1_700_000_000, 0));
+    let new = Uuid::new_v7(Timestamp::from_unix(NoContext, 1_700_000_100, 0));
+    let v4 = Uuid::new_v4();
+
+    let mut map: UuidMap<_> = [(old, 0), (new, 1), (v4, 2)].into_iter().collect();
+    let newer = map.split_off_after(UNIX_EPOCH + Duration::from_secs(1_700_000_050));
+
+    assert_eq!(newer.len(), 1);
+    assert_eq!(newer.get(&new), Some(&1));
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&old), Some(&0));
+    assert_eq!(map.get(&v4), Some(&2));
+}
+
+#[test]
+fn iter_bucket_order() {
+    let map: UuidMap<_> = (0..100).map(|idx| (Uuid::new_v4(), idx)).collect();
+
+    let mut visited = UuidSet::new();
+    for (uuid, value) in map.iter_bucket_order() {
+        assert!(visited.insert(*uuid));
+        assert_eq!(map.get(uuid), Some(value));
+    }
+
+    assert_eq!(visited.len(), map.len());
+}
+
+#[cfg(feature = "serde-1")]
+#[test]
+fn deserialize_all_v7() {
+    #[derive(serde::Deserialize)]
+    struct Contract {
+        #[serde(deserialize_with = "super::deserialize_all_v7")]
+        entries: UuidMap<i32>,
+    }
+
+    let (v7, v4) = (Uuid::now_v7(), Uuid::new_v4());
+
+    let json = format!(r#"{{"entries": {{"{v7}": 0}}}}"#);
+    let contract: Contract = serde_json::from_str(&json).unwrap();
+    assert_eq!(contract.entries.get(&v7), Some(&0));
+
+    let json = format!(r#"{{"entries": {{"{v7}": 0, "{v4}": 1}}}}"#);
+    let err = serde_json::from_str::<Contract>(&json).err().unwrap();
+    assert!(err.to_string().contains(&v4.to_string()));
+}
+
+#[test]
+fn retain_in_set() {
+    let mut map: UuidMap<_> = (0..100).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let removed: Vec<_> = map.keys().skip(10).copied().collect();
+    let keep: UuidSet = map.keys().take(10).copied().collect();
+
+    map.retain_in_set(&keep);
+
+    assert_eq!(map.len(), 10);
+    assert!(map.keys().all(|uuid| keep.contains(uuid)));
+    assert!(removed.iter().all(|uuid| !map.contains_key(uuid)));
+}
+
+#[cfg(feature = "rkyv-08")]
+#[test]
+fn rkyv_endianness() {
+    use rkyv::{Archive, rancor};
+
+    let uuid = Uuid::from_u128(0x0123_4567_89ab_7def_8123_4567_89ab_cdef);
+
+    // The hash is read from the bytes of the UUID in big-endian order, regardless of
+    // the endianness of the platform.
+    assert_eq!(UuidBuildHasher.hash_one(uuid), 0xef23_4567_89ab_cdef);
+
+    let map = UuidMap::from([(uuid, 1_u32)]);
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+
+    // UUIDs are archived as their bytes, which don't depend on the endianness of the
+    // archive either.
+    assert!(bytes.windows(16).any(|window| window == uuid.as_bytes()));
+
+    let archived =
+        rkyv::access::<<UuidMap<u32> as Archive>::Archived, rancor::Error>(&bytes).unwrap();
+    assert_eq!(archived.get(&uuid).map(|value| value.to_native()), Some(1));
+    assert!(archived.contains_key(&uuid));
+}
+
+#[cfg(feature = "rkyv-08")]
+#[test]
+fn archived_map_iter_prefetched() {
+    use rkyv::{Archive, rancor};
+
+    let map: UuidMap<u32> = (0..1_000).map(|idx| (Uuid::new_v4(), idx)).collect();
+
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    // SAFETY: `bytes` was just produced by serializing a `UuidMap`.
+    let archived = unsafe { rkyv::access_unchecked::<<UuidMap<u32> as Archive>::Archived>(&bytes) };
+
+    let iter = archived.iter_prefetched();
+    assert_eq!(iter.len(), map.len());
+
+    let mut visited = UuidSet::new();
+    for (uuid, value) in iter {
+        assert!(visited.insert(*uuid));
+        assert_eq!(map.get(uuid), Some(&value.to_native()));
+    }
+
+    assert_eq!(visited.len(), map.len());
+}
+
+#[test]
+fn sequential_v7() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let step = Duration::from_millis(250);
+
+    let set = UuidSet::sequential_v7(start, 100, step);
+    assert_eq!(set, UuidSet::sequential_v7(start, 100, step));
+    assert_eq!(set.len(), 100);
+
+    let mut uuids: Vec<_> = set.into_iter().collect();
+    uuids.sort_unstable();
+
+    for (idx, uuid) in uuids.iter().enumerate() {
+        let (secs, nanos) = uuid.get_timestamp().unwrap().to_unix();
+        let time = UNIX_EPOCH + Duration::new(secs, nanos);
+
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(time, start + step * idx as u32);
+    }
+
+    // Much more than 65536 UUIDs within a single millisecond.
+    let set = UuidSet::sequential_v7(start, 100_000, Duration::from_nanos(1));
+    assert_eq!(set.len(), 100_000);
+    assert_eq!(set.distinct_v7_milliseconds(), 1);
+}
+
+#[test]
+fn version_histogram() {
+    let map: UuidMap<_> = (0..3)
+        .map(|_| Uuid::new_v4())
+        .chain((0..5).map(|_| Uuid::now_v7()))
+        .zip(0..)
+        .collect();
+
+    let mut expected = [0; 16];
+    expected[4] = 3;
+    expected[7] = 5;
+
+    assert_eq!(map.version_histogram(), expected);
+}
+
+#[test]
+fn ring_position() {
+    let map: UuidMap<_> = (0..8_000).map(|idx| (Uuid::now_v7(), idx)).collect();
+    let ring_size = NonZeroU64::new(1 << 32).unwrap();
+
+    let uuid = map.keys().next().unwrap();
+    let position = UuidBuildHasher.ring_position(uuid, ring_size);
+    assert_eq!(UuidBuildHasher.ring_position(uuid, ring_size), position);
+    assert!(position < ring_size.get());
+
+    // Eight virtual nodes spread evenly across the ring, in a shuffled order.
+    let nodes = [3, 0, 6, 1, 7, 4, 2, 5].map(|idx| idx << 29);
+    let groups = map.keys_by_ring_node(&nodes, ring_size);
+
+    assert_eq!(groups.len(), 8);
+    assert_eq!(
+        groups.iter().map(|group| group.len()).sum::<usize>(),
+        map.len()
+    );
+    assert!(groups.iter().all(|group| group.len().abs_diff(1_000) < 250));
+
+    for (node, group) in nodes.iter().zip(&groups) {
+        for uuid in group.iter() {
+            let position = UuidBuildHasher.ring_position(uuid, ring_size);
+            let distance = (node + ring_size.get() - position) % ring_size.get();
+            assert!(distance < 1 << 29);
+        }
+    }
+
+    // The UUIDs after the highest virtual node wrap around to the lowest one.
+    let lowest = &groups[1];
+    let wrapped: Vec<_> = map
+        .keys()
+        .filter(|uuid| UuidBuildHasher.ring_position(uuid, ring_size) > 7 << 29)
+        .collect();
+
+    assert!(!wrapped.is_empty());
+    assert!(wrapped.iter().all(|uuid| lowest.contains(uuid)));
+
+    assert!(map.keys_by_ring_node(&[], ring_size).is_empty());
+}
+
+#[test]
+fn into_grouped_by_shard() {
+    let map: UuidMap<_> = (0..100).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let shards = NonZeroUsize::new(4).unwrap();
+
+    let groups = map.clone().into_grouped_by_shard(shards);
+
+    assert_eq!(groups.len(), 4);
+    assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), map.len());
+
+    for (shard, group) in groups.iter().enumerate() {
+        for (uuid, value) in group {
+            assert_eq!(UuidBuildHasher.shard_of(uuid, shards), shard);
+            assert_eq!(map.get(uuid), Some(value));
+        }
+    }
+}
+
+#[cfg(feature = "speedy-08")]
+#[test]
+fn speedy_window() {
+    let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let v7s: Vec<_> = UuidSet::sequential_v7(start, 6, Duration::from_secs(1))
+        .into_iter()
+        .collect();
+
+    let mut map: UuidMap<_> = v7s.iter().map(|uuid| (*uuid, 0_u32)).collect();
+    map.insert(Uuid::new_v4(), 0);
+    let mut base = map.clone();
+
+    for value in map.values_mut() {
+        *value = 1;
+    }
+    let uuid = Uuid::now_v7();
+    map.insert(uuid, 2);
+
+    let checkpoint = start + Duration::from_millis(3_500);
+    let mut stream = Vec::new();
+    map.serialize_window(checkpoint, &mut stream).unwrap();
+    map.serialize_window(SystemTime::now(), &mut stream)
+        .unwrap();
+
+    let mut reader = stream.as_slice();
+    base.merge_window(&mut reader).unwrap();
+    base.merge_window(&mut reader).unwrap();
+    assert!(reader.is_empty());
+
+    assert_eq!(base.len(), 8);
+    assert_eq!(base[&uuid], 2);
+    for uuid in &v7s {
+        let expected = (v7_time(uuid).unwrap() > checkpoint) as u32;
+        assert_eq!(base[uuid], expected);
+    }
+}
+
+#[cfg(feature = "speedy-08")]
+#[test]
+fn logged_uuid_map() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+
+    let mut map = LoggedUuidMap::new(Vec::new());
+    map.insert(uuids[0], 0u32).unwrap();
+    map.insert(uuids[1], 1).unwrap();
+    map.insert(uuids[2], 2).unwrap();
+    assert_eq!(map.insert(uuids[1], 10).unwrap(), Some(1));
+    assert_eq!(map.remove(&uuids[2]).unwrap(), Some(2));
+    assert_eq!(map.remove(&uuids[3]).unwrap(), None);
+
+    let (map, mut log) = map.into_parts();
+    let recovered = LoggedUuidMap::<u32, _>::recover_from_log(&log[..], Vec::new()).unwrap();
+    assert_eq!(*recovered, map);
+
+    // A record truncated by a crash is ignored.
+    let mut recovered = recovered;
+    recovered.insert(uuids[3], 3).unwrap();
+    let (_, tail) = recovered.into_parts();
+    log.extend_from_slice(&tail[..tail.len() - 1]);
+
+    let recovered = LoggedUuidMap::<u32, _>::recover_from_log(&log[..], Vec::new()).unwrap();
+    assert_eq!(*recovered, map);
+
+    let mut log = vec![0];
+    log.extend_from_slice(UNSUPPORTED.as_bytes());
+    log.extend_from_slice(&0u32.to_le_bytes());
+    assert!(LoggedUuidMap::<u32, _>::recover_from_log(&log[..], Vec::new()).is_err());
+}
+
+#[cfg(feature = "speedy-08")]
+uuid_like::wrapper!(SpeedyId);
+
+#[cfg(feature = "speedy-08")]
+impl<'a, C: speedy::Context> speedy::Readable<'a, C> for SpeedyId {
+    fn read_from<R: speedy::Reader<'a, C>>(reader: &mut R) -> Result<Self, C::Error> {
+        Uuid::read_from(reader).map(Self)
+    }
+}
+
+#[cfg(feature = "speedy-08")]
+impl<C: speedy::Context> speedy::Writable<C> for SpeedyId {
+    fn write_to<W: ?Sized + speedy::Writer<C>>(&self, writer: &mut W) -> Result<(), C::Error> {
+        self.0.write_to(writer)
+    }
+}
+
+#[cfg(feature = "speedy-08")]
+#[test]
+fn speedy() {
+    use speedy::{Readable, Writable};
+    use uuid_like::UuidLike;
+
+    let map: UuidMap<u32> = (0..10).map(|idx| (Uuid::new_v4(), idx)).collect();
+    let bytes = map.write_to_vec().unwrap();
+    assert_eq!(UuidMap::read_from_buffer(&bytes).unwrap(), map);
+
+    let map: UuidIndexMap<u32> = (0..10).map(|idx| (Uuid::now_v7(), idx)).collect();
+    let bytes = map.write_to_vec().unwrap();
+    let read = UuidIndexMap::read_from_buffer(&bytes).unwrap();
+    assert!(read.iter().eq(map.iter()));
+
+    let set: UuidSet = (0..10).map(|_| Uuid::new_v4()).collect();
+    let bytes = set.write_to_vec().unwrap();
+    assert_eq!(UuidSet::read_from_buffer(&bytes).unwrap(), set);
+
+    let set: UuidIndexSet = (0..10).map(|_| Uuid::now_v7()).collect();
+    let bytes = set.write_to_vec().unwrap();
+    let read = UuidIndexSet::read_from_buffer(&bytes).unwrap();
+    assert!(read.iter().eq(set.iter()));
+
+    // The index collections are built from UUIDv4s, so that their order isn't sorted.
+    let ids: [_; 10] = array::from_fn(|_| SpeedyId::from_bytes(Uuid::new_v4().into_bytes()));
+
+    let map: UuidLikeMap<_, u32> = ids.into_iter().zip(0..).collect();
+    let bytes = map.write_to_vec().unwrap();
+    assert_eq!(UuidLikeMap::read_from_buffer(&bytes).unwrap(), map);
+
+    let map: UuidLikeIndexMap<_, u32> = ids.into_iter().zip(0..).collect();
+    let bytes = map.write_to_vec().unwrap();
+    let read = UuidLikeIndexMap::<SpeedyId, u32>::read_from_buffer(&bytes).unwrap();
+    assert!(read.keys().eq(&ids));
+
+    let set: UuidLikeSet<_> = ids.into_iter().collect();
+    let bytes = set.write_to_vec().unwrap();
+    assert_eq!(UuidLikeSet::read_from_buffer(&bytes).unwrap(), set);
+
+    let set: UuidLikeIndexSet<_> = ids.into_iter().rev().collect();
+    let bytes = set.write_to_vec().unwrap();
+    let read = UuidLikeIndexSet::<SpeedyId>::read_from_buffer(&bytes).unwrap();
+    assert!(read.iter().eq(ids.iter().rev()));
+
+    #[cfg(not(feature = "gxhash-3"))]
+    {
+        let mut bytes = 1u32.to_le_bytes().to_vec();
+        bytes.extend_from_slice(Uuid::now_v1(&[0; 6]).as_bytes());
+        assert!(UuidSet::read_from_buffer(&bytes).is_err());
+    }
+}
+
+#[test]
+fn merge_time_ordered() {
+    let uuids: Vec<_> = (0..10).map(|_| Uuid::now_v7()).collect();
+
+    let a: UuidIndexMap<_> = uuids.iter().step_by(2).map(|uuid| (*uuid, 'a')).collect();
+    let b: UuidIndexMap<_> = uuids
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| idx % 2 == 1 || *idx == 4)
+        .map(|(_, uuid)| (*uuid, 'b'))
+        .collect();
+
+    let merged = UuidIndexMap::merge_time_ordered(a, b);
+
+    assert!(merged.keys().eq(&uuids));
+    assert!(merged.keys().is_sorted());
+    assert_eq!(merged.get(&uuids[0]), Some(&'a'));
+    assert_eq!(merged.get(&uuids[1]), Some(&'b'));
+    assert_eq!(merged.get(&uuids[4]), Some(&'b'));
+}
+
+#[test]
+fn from_iter_with_stats() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let entries = [uuids[0], uuids[1], uuids[0], uuids[2], uuids[0], uuids[1]];
+
+    let (map, stats) = UuidMap::from_iter_with_stats(entries.into_iter().zip(0..));
+
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get(&uuids[0]), Some(&4));
+    assert_eq!(stats.inserted, 6);
+    assert_eq!(stats.overwritten, 3);
+    assert_eq!(stats.capacity, map.capacity());
+}
+
+#[test]
+fn key_difference() {
+    let uuids: [_; 4] = array::from_fn(|_| Uuid::new_v4());
+
+    let before: UuidMap<_> = uuids[..3].iter().map(|uuid| (*uuid, 0)).collect();
+    let after: UuidMap<_> = uuids[1..].iter().map(|uuid| (*uuid, "value")).collect();
+
+    let removed = before.key_difference(&after);
+    let added = after.key_difference(&before);
+
+    assert_eq!(removed.len(), 1);
+    assert!(removed.contains(&uuids[0]));
+    assert_eq!(added.len(), 1);
+    assert!(added.contains(&uuids[3]));
+}
+
+#[test]
+fn same_keys() {
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+
+    let numbers: UuidMap<_> = uuids.iter().map(|uuid| (*uuid, 0)).collect();
+    let strings: UuidMap<_> = uuids.iter().map(|uuid| (*uuid, "value")).collect();
+    let partial: UuidMap<_> = uuids[1..].iter().map(|uuid| (*uuid, 0)).collect();
+
+    let mut other = partial.clone();
+    other.insert(Uuid::new_v4(), 0);
+
+    assert!(numbers.same_keys(&strings));
+    assert!(!numbers.same_keys(&partial));
+    assert!(!partial.same_keys(&numbers));
+    assert!(!numbers.same_keys(&other));
+}
+
+#[test]
+fn iter_with_shard() {
+    let map: UuidMap<_> = (0..100).map(|idx| (Uuid::now_v7(), idx)).collect();
+    let shards = NonZeroUsize::new(3).unwrap();
+
+    let mut count = 0;
+    for (shard, uuid, value) in map.iter_with_shard(shards) {
+        assert_eq!(shard, UuidBuildHasher.shard_of(uuid, shards));
+        assert_eq!(map.get(uuid), Some(value));
+        count += 1;
+    }
+
+    assert_eq!(count, map.len());
+}
+
+#[test]
+fn reserve_for_estimate() {
+    let mut map: UuidMap<_> = (0..10).map(|idx| (Uuid::new_v4(), idx)).collect();
+
+    map.reserve_for_estimate(1_000, 0.05);
+    assert!(map.capacity() >= 1_050);
+
+    map.reserve_for_estimate(0, 0.0);
+    assert!(map.capacity() >= 1_050);
+}
+
+#[cfg(feature = "dashmap-6")]
+#[test]
+fn dash_map() {
+    use std::thread;
+
+    let map = UuidDashMap::with_shard_amount(8);
+    let uuids: Vec<[_; 100]> = (0..4).map(|_| array::from_fn(|_| Uuid::new_v4())).collect();
+
+    thread::scope(|scope| {
+        for (thread, uuids) in uuids.iter().enumerate() {
+            let map = &map;
+            scope.spawn(move || {
+                for uuid in uuids {
+                    map.insert(*uuid, thread);
+                }
+            });
+        }
+    });
+
+    assert_eq!(map.len(), 400);
+    assert_eq!(*map.get(&uuids[2][7]).unwrap(), 2);
+    assert_eq!(map.remove(&uuids[3][0]), Some((uuids[3][0], 3)));
+    assert!(!map.contains_key(&uuids[3][0]));
+    assert!(UuidDashMap::<()>::with_capacity(16).is_empty());
+}
+
+#[cfg(feature = "rayon-1")]
+#[test]
+fn par_from_keys() {
+    let keys = UuidSet::sequential_v7(SystemTime::now(), 1_000, Duration::from_millis(1));
+    let f = |uuid: &Uuid| {
+        uuid.as_bytes()
+            .iter()
+            .map(|byte| byte.count_ones())
+            .sum::<u32>()
+    };
+    let map = UuidMap::par_from_keys(keys.clone(), f).unwrap();
+
+    assert_eq!(map.len(), 1_000);
+    assert!(keys.iter().all(|uuid| map[uuid] == f(uuid)));
+    assert_eq!(map, UuidMap::from_keys(keys, f).unwrap());
+
+    let keys = vec![Uuid::new_v4(), UNSUPPORTED];
+    let err = UuidMap::par_from_keys(keys, f).unwrap_err();
+    assert_eq!(err, UnsupportedVersionError(UNSUPPORTED));
+}
+
+#[cfg(feature = "rayon-1")]
+#[test]
+fn rayon_ref() {
+    use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+
+    let mut map: UuidMap<u64> = (0..1_000).map(|idx| (Uuid::new_v4(), idx)).collect();
+    map.par_iter_mut().for_each(|(_, value)| *value *= 2);
+
+    let shared = &map;
+    let sum = rayon::join(
+        || shared.par_iter().map(|(_, value)| value).sum::<u64>(),
+        || shared.values().sum::<u64>(),
+    );
+    assert_eq!(sum, (999_000, 999_000));
+
+    let set: UuidIndexSet = map.keys().copied().collect();
+    assert_eq!(
+        set.par_iter()
+            .filter(|uuid| map.contains_key(*uuid))
+            .count(),
+        1_000
+    );
+}
+
+// A `UuidLike` key which is its own archived type, as required by the archived
+// `UuidLike` collections.
+#[cfg(feature = "rkyv-08")]
+uuid_like::wrapper! {
+    #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize, rkyv::Portable)]
+    #[derive(rkyv::bytecheck::CheckBytes)]
+    #[bytecheck(crate = rkyv::bytecheck)]
+    #[rkyv(as = RkyvId)]
+    #[repr(transparent)]
+    RkyvId
+}
+
+#[cfg(feature = "rkyv-08")]
+#[test]
+fn rkyv_index_order() {
+    use rkyv::{Archive, rancor};
+    use uuid_like::UuidLike;
+
+    let uuids: [_; 8] = array::from_fn(|_| Uuid::new_v4());
+
+    let map: UuidIndexMap<u32> = uuids.into_iter().zip(0..).collect();
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    let archived =
+        rkyv::access::<<UuidIndexMap<u32> as Archive>::Archived, rancor::Error>(&bytes).unwrap();
+    assert!(archived.keys().eq(&uuids));
+
+    let map: UuidIndexMap<u32> = rkyv::deserialize::<_, rancor::Error>(archived).unwrap();
+    assert!(map.keys().eq(&uuids));
+
+    let set: UuidIndexSet = uuids.into_iter().rev().collect();
+    let bytes = rkyv::to_bytes::<rancor::Error>(&set).unwrap();
+    let archived =
+        rkyv::access::<<UuidIndexSet as Archive>::Archived, rancor::Error>(&bytes).unwrap();
+    assert!(archived.iter().eq(uuids.iter().rev()));
+
+    let set: UuidIndexSet = rkyv::deserialize::<_, rancor::Error>(archived).unwrap();
+    assert!(set.iter().eq(uuids.iter().rev()));
+
+    let keys = uuids.map(|uuid| RkyvId::from_bytes(uuid.into_bytes()));
+
+    let map: UuidLikeIndexMap<_, u32> = keys.into_iter().zip(0..).collect();
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    let archived =
+        rkyv::access::<<UuidLikeIndexMap<RkyvId, u32> as Archive>::Archived, rancor::Error>(&bytes)
+            .unwrap();
+    assert!(archived.keys().eq(&keys));
+
+    let map: UuidLikeIndexMap<RkyvId, u32> =
+        rkyv::deserialize::<_, rancor::Error>(archived).unwrap();
+    assert!(map.keys().eq(&keys));
+
+    let set: UuidLikeIndexSet<_> = keys.into_iter().rev().collect();
+    let bytes = rkyv::to_bytes::<rancor::Error>(&set).unwrap();
+    let archived =
+        rkyv::access::<<UuidLikeIndexSet<RkyvId> as Archive>::Archived, rancor::Error>(&bytes)
+            .unwrap();
+    assert!(archived.iter().eq(keys.iter().rev()));
+
+    let set: UuidLikeIndexSet<RkyvId> = rkyv::deserialize::<_, rancor::Error>(archived).unwrap();
+    assert!(set.iter().eq(keys.iter().rev()));
+}
+
+#[cfg(feature = "rkyv-08")]
+#[test]
+fn rkyv_load_factor() {
+    use rkyv::{Archive, rancor, with::With};
+
+    let map: UuidMap<u32> = (0..1_000).map(|idx| (Uuid::new_v4(), idx)).collect();
+
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    let sparse_bytes =
+        rkyv::to_bytes::<rancor::Error>(With::<_, LoadFactor<1, 2>>::cast(&map)).unwrap();
+    assert!(sparse_bytes.len() > bytes.len());
+
+    let archived =
+        rkyv::access::<<UuidMap<u32> as Archive>::Archived, rancor::Error>(&sparse_bytes).unwrap();
+    assert_eq!(*archived, map);
+
+    let deserialized: UuidMap<u32> = rkyv::deserialize::<_, rancor::Error>(archived).unwrap();
+    assert_eq!(deserialized, map);
+}
+
+#[cfg(feature = "rkyv-08")]
+#[test]
+fn rkyv_check_bytes() {
+    use rkyv::{Archive, rancor};
+
+    type ArchivedMap = <UuidMap<u32> as Archive>::Archived;
+    type ArchivedSet = <UuidSet as Archive>::Archived;
+
+    let uuids: [_; 3] = array::from_fn(|_| Uuid::new_v4());
+    let map: UuidMap<u32> = uuids.iter().copied().zip(0..).collect();
+    let set: UuidSet = uuids.iter().copied().collect();
+
+    let mut map_bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    let mut set_bytes = rkyv::to_bytes::<rancor::Error>(&set).unwrap();
+    assert_eq!(
+        *rkyv::access::<ArchivedMap, rancor::Error>(&map_bytes).unwrap(),
+        map
+    );
+    assert_eq!(
+        *rkyv::access::<ArchivedSet, rancor::Error>(&set_bytes).unwrap(),
+        set
+    );
+
+    // Gives the first UUID the version and variant of `UNSUPPORTED`.
+    for bytes in [&mut map_bytes[..], &mut set_bytes[..]] {
+        let pos = bytes
+            .windows(16)
+            .position(|window| window == uuids[0].as_bytes())
+            .unwrap();
+        bytes[pos + 6] = 0x70;
+        bytes[pos + 8] = 0xc0;
+    }
+
+    assert!(rkyv::access::<ArchivedMap, rancor::Error>(&map_bytes).is_err());
+    assert!(rkyv::access::<ArchivedSet, rancor::Error>(&set_bytes).is_err());
+}
+
+#[cfg(feature = "rkyv-08-sorted")]
+#[test]
+fn rkyv_sorted() {
+    use rkyv::rancor;
+
+    // UUIDv7s which only differ by their timestamp, and thus have the same hash.
+    let colliding = (0..100)
+        .map(|millis| uuid::Builder::from_unix_timestamp_millis(millis, &[0x42; 10]).into_uuid());
+
+    let entries: Vec<_> = (0..1_000)
+        .map(|_| Uuid::new_v4())
+        .chain(colliding)
+        .zip(0..)
+        .collect();
+
+    let map: UuidMap<u32> = entries.iter().copied().collect();
+    let reversed: UuidMap<u32> = entries.iter().rev().copied().collect();
+
+    let bytes = rkyv::to_bytes::<rancor::Error>(&map).unwrap();
+    let reversed_bytes = rkyv::to_bytes::<rancor::Error>(&reversed).unwrap();
+    assert_eq!(bytes[..], reversed_bytes[..]);
+
+    let set: UuidSet = entries.iter().map(|(uuid, _)| *uuid).collect();
+    let reversed: UuidSet = entries.iter().rev().map(|(uuid, _)| *uuid).collect();
+
+    let bytes = rkyv::to_bytes::<rancor::Error>(&set).unwrap();
+    let reversed_bytes = rkyv::to_bytes::<rancor::Error>(&reversed).unwrap();
+    assert_eq!(bytes[..], reversed_bytes[..]);
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::BTreeSet,
+    fmt::{self, Debug, Formatter},
+    ops::Deref,
+    time::Duration,
+};
+
+use uuid::Uuid;
+
+use crate::{UuidMap, v7_time};
+
+/// A [`UuidMap`] of UUIDv7s which only keeps the entries within a sliding time
+/// window.
+///
+/// Each time an entry is inserted, the entries whose UUID is older than `window`,
+/// relative to the most recent UUID of the map, are evicted.
+///
+/// See [`V7WindowAggregator::insert()`].
+pub struct V7WindowAggregator<V> {
+    map: UuidMap<V>,
+    // UUIDv7s are ordered by their timestamp first, so this is in chronological order.
+    order: BTreeSet<Uuid>,
+    window: Duration,
+}
+
+impl<V> V7WindowAggregator<V> {
+    /// Creates an empty [`V7WindowAggregator`] keeping the entries within `window`.
+    #[inline]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            map: UuidMap::new(),
+            order: BTreeSet::new(),
+            window,
+        }
+    }
+
+    /// Returns the duration of the window.
+    #[inline]
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Inserts an entry, then evicts the entries older than the window.
+    ///
+    /// Returns the previous value for the UUID, if there was one. The entry itself is
+    /// evicted right away if it is older than the window.
+    ///
+    /// ## Panics
+    ///
+    /// This will panic if `uuid` isn't a UUIDv7.
+    pub fn insert(&mut self, uuid: Uuid, value: V) -> Option<V> {
+        assert!(v7_time(&uuid).is_some(), "expected a UUIDv7");
+
+        let previous = self.map.insert(uuid, value);
+        self.order.insert(uuid);
+
+        let newest = self.order.last().and_then(v7_time);
+        let cutoff = newest.and_then(|newest| newest.checked_sub(self.window));
+
+        if let Some(cutoff) = cutoff {
+            while let Some(oldest) = self.order.first() {
+                if v7_time(oldest).is_some_and(|time| time >= cutoff) {
+                    break;
+                }
+
+                self.map.remove(oldest);
+                self.order.pop_first();
+            }
+        }
+
+        previous
+    }
+}
+
+impl<V> Deref for V7WindowAggregator<V> {
+    type Target = UuidMap<V>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<V: Debug> Debug for V7WindowAggregator<V> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}